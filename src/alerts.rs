@@ -0,0 +1,295 @@
+use std::collections::HashSet;
+
+use futures::future::BoxFuture;
+use serde::Serialize;
+
+use crate::api::XUiClient;
+use crate::errors::MyError;
+use crate::models::ClientTraffic;
+use crate::ops::{MILLIS_PER_DAY, now_millis};
+
+/// A condition on a client's traffic counters raising an [`Alert`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AlertRule {
+    /// The client used at least `percent` percent of its quota. Clients without quota are
+    /// ignored.
+    QuotaUsed { percent: u8 },
+    /// The client expires within `days` days (or already expired). Clients without expiry
+    /// or waiting for their first connection are ignored.
+    ExpiresWithin { days: u32 },
+    /// The client was last online at least `days` days ago. Clients never seen online are
+    /// ignored.
+    InactiveFor { days: u32 },
+}
+
+impl AlertRule {
+    /// Returns a description of the alert if `traffic` matches the rule at `now`.
+    fn check(&self, traffic: &ClientTraffic, now: i64) -> Option<String> {
+        match *self {
+            AlertRule::QuotaUsed { percent } => {
+                let matched = traffic.total > 0
+                    && u128::from(traffic.used()) * 100
+                        >= u128::from(traffic.total) * u128::from(percent);
+                matched.then(|| {
+                    format!(
+                        "{} used {}% of its quota ({} of {} bytes)",
+                        traffic.email,
+                        u128::from(traffic.used()) * 100 / u128::from(traffic.total),
+                        traffic.used(),
+                        traffic.total
+                    )
+                })
+            }
+            AlertRule::ExpiresWithin { days } => {
+                let matched = traffic.expiry_time > 0
+                    && traffic.expiry_time - now <= i64::from(days) * MILLIS_PER_DAY;
+                matched.then(|| {
+                    let remaining = traffic.expiry_time - now;
+                    if remaining <= 0 {
+                        format!("{} has expired", traffic.email)
+                    } else {
+                        format!(
+                            "{} expires in {} hours",
+                            traffic.email,
+                            remaining / (60 * 60 * 1000)
+                        )
+                    }
+                })
+            }
+            AlertRule::InactiveFor { days } => {
+                let matched = traffic.last_online > 0
+                    && now - traffic.last_online >= i64::from(days) * MILLIS_PER_DAY;
+                matched.then(|| {
+                    format!(
+                        "{} has been inactive for {} days",
+                        traffic.email,
+                        (now - traffic.last_online) / MILLIS_PER_DAY
+                    )
+                })
+            }
+        }
+    }
+}
+
+/// A rule matched by a client, as dispatched to the [`AlertSink`]s.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Alert {
+    pub rule: AlertRule,
+    pub email: String,
+    pub inbound_id: u64,
+    pub message: String,
+    /// Time of the evaluation, as a Unix timestamp in milliseconds.
+    pub timestamp: i64,
+    /// The counters the rule was evaluated on.
+    pub traffic: ClientTraffic,
+}
+
+/// A destination of alerts.
+///
+/// Implemented by [`CallbackSink`], [`WebhookSink`] and [`StdoutSink`].
+pub trait AlertSink: Send + Sync {
+    /// Delivers one alert.
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<(), MyError>>;
+}
+
+/// Calls a function for every alert.
+pub struct CallbackSink<F> {
+    callback: F,
+}
+
+impl<F> CallbackSink<F>
+where
+    F: Fn(&Alert) + Send + Sync,
+{
+    /// Creates a sink calling `callback` with every alert.
+    pub fn new(callback: F) -> Self {
+        Self { callback }
+    }
+}
+
+impl<F> AlertSink for CallbackSink<F>
+where
+    F: Fn(&Alert) + Send + Sync,
+{
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<(), MyError>> {
+        (self.callback)(alert);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Posts every alert as JSON to a URL.
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    url: String,
+    http: reqwest::Client,
+}
+
+impl WebhookSink {
+    /// Creates a sink posting alerts to `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+impl AlertSink for WebhookSink {
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<(), MyError>> {
+        Box::pin(async move {
+            self.http
+                .post(&self.url)
+                .json(alert)
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+/// Prints every alert to the standard output as a line of JSON.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutSink;
+
+impl AlertSink for StdoutSink {
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<(), MyError>> {
+        Box::pin(async move {
+            println!("{}", serde_json::to_string(alert)?);
+            Ok(())
+        })
+    }
+}
+
+/// Evaluates [`AlertRule`]s on client traffic counters and dispatches new alerts to
+/// [`AlertSink`]s, typically once per poll.
+///
+/// An alert is raised once per client and rule: it is raised again only after the
+/// condition stopped matching (e.g. the quota was raised or the subscription renewed) and
+/// matches again. A failing sink is logged and does not prevent the other sinks from
+/// receiving the alert.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use xui_rs::alerts::{AlertEngine, AlertRule, StdoutSink, WebhookSink};
+/// use xui_rs::api::XUiClient;
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let mut client = XUiClient::new("https://your-xui-panel.com/")?
+///         .with_credentials("admin", "password");
+///
+///     let mut engine = AlertEngine::new()
+///         .rule(AlertRule::QuotaUsed { percent: 80 })
+///         .rule(AlertRule::QuotaUsed { percent: 95 })
+///         .rule(AlertRule::ExpiresWithin { days: 3 })
+///         .sink(StdoutSink)
+///         .sink(WebhookSink::new("https://hooks.example.com/xui"));
+///
+///     loop {
+///         engine.check(&mut client).await?;
+///         tokio::time::sleep(Duration::from_secs(60)).await;
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    sinks: Vec<Box<dyn AlertSink>>,
+    raised: HashSet<(String, AlertRule)>,
+}
+
+impl AlertEngine {
+    /// Creates an engine without rules or sinks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule.
+    pub fn rule(mut self, rule: AlertRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Adds a sink receiving every new alert.
+    pub fn sink(mut self, sink: impl AlertSink + 'static) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    /// Returns the alerts raised by `traffic` at `now` (Unix milliseconds) that were not
+    /// raised before, without dispatching them.
+    ///
+    /// `traffic` may cover only part of the clients, e.g. a single inbound: clients missing
+    /// from it keep their raised alerts.
+    pub fn evaluate(&mut self, traffic: &[ClientTraffic], now: i64) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        let mut raised = HashSet::new();
+        let evaluated: HashSet<&str> = traffic.iter().map(|stats| stats.email.as_str()).collect();
+
+        for stats in traffic {
+            for rule in &self.rules {
+                let Some(message) = rule.check(stats, now) else {
+                    continue;
+                };
+                let key = (stats.email.clone(), *rule);
+                if !self.raised.contains(&key) {
+                    alerts.push(Alert {
+                        rule: *rule,
+                        email: stats.email.clone(),
+                        inbound_id: stats.inbound_id,
+                        message,
+                        timestamp: now,
+                        traffic: stats.clone(),
+                    });
+                }
+                raised.insert(key);
+            }
+        }
+
+        // Conditions that stopped matching for the evaluated clients are re-armed
+        self.raised
+            .retain(|(email, _)| !evaluated.contains(email.as_str()));
+        self.raised.extend(raised);
+        alerts
+    }
+
+    /// Evaluates `traffic` at `now` and sends the new alerts to every sink.
+    ///
+    /// # Returns
+    ///
+    /// The alerts that were dispatched.
+    pub async fn process(&mut self, traffic: &[ClientTraffic], now: i64) -> Vec<Alert> {
+        let alerts = self.evaluate(traffic, now);
+
+        for alert in &alerts {
+            for sink in &self.sinks {
+                if let Err(err) = sink.send(alert).await {
+                    tracing::warn!(email = %alert.email, error = %err, "alert sink failed");
+                }
+            }
+        }
+
+        alerts
+    }
+
+    /// Fetches the client counters of all inbounds of the panel and processes them.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `MyError` if the inbounds could not be fetched; sink failures are only
+    /// logged.
+    pub async fn check(&mut self, client: &mut XUiClient) -> Result<Vec<Alert>, MyError> {
+        let traffic: Vec<ClientTraffic> = client
+            .get_inbounds_typed()
+            .await?
+            .into_iter()
+            .flat_map(|inbound| inbound.client_stats)
+            .collect();
+
+        Ok(self.process(&traffic, now_millis()).await)
+    }
+}
@@ -1,11 +1,16 @@
 use regex::Regex;
 use reqwest::header::COOKIE;
-use reqwest::{Client, IntoUrl};
+use reqwest::{Client, IntoUrl, Method, Response, StatusCode};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tracing::Instrument;
 
 use crate::errors::MyError;
+use crate::metrics::{ErrorKind, RequestMetrics, RequestRecord};
+use crate::models::{ApiResponse, Inbound, ServerStatus, parse_inbounds};
 
+#[derive(Clone)]
 pub struct XUiClient {
     client: Client,
     panel_base_url: url::Url,
@@ -13,6 +18,7 @@ pub struct XUiClient {
     cookie_expiry: Option<Instant>,
     username: Option<String>,
     password: Option<String>,
+    metrics: Option<Arc<dyn RequestMetrics>>,
 }
 
 impl XUiClient {
@@ -65,6 +71,7 @@ impl XUiClient {
             cookie_expiry: None,
             username: None,
             password: None,
+            metrics: None,
         })
     }
 
@@ -90,6 +97,41 @@ impl XUiClient {
         }
     }
 
+    /// Stores credentials without logging in: the client logs in on its first request
+    /// and again whenever the session expires.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client =
+    ///         XUiClient::new("https://your-xui-panel.com/")?.with_credentials("admin", "password");
+    ///     let inbounds = client.get_inbounds().await?;
+    ///     println!("Inbounds: {}", inbounds);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.username = Some(username.into());
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Installs a hook receiving a [`RequestRecord`] for every request sent to the panel.
+    ///
+    /// Requests are traced in `xui_request` spans whether or not a hook is installed; see
+    /// [`RequestMetrics`] for an example.
+    pub fn with_metrics(mut self, metrics: Arc<dyn RequestMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     /// Logs in to the 3X-UI panel using the provided username and password.
     ///
     /// This function sends a POST request to the login endpoint with the given username and password
@@ -130,38 +172,51 @@ impl XUiClient {
         let username_str: String = username.into();
         let password_str: String = password.into();
 
-        let mut params = HashMap::new();
-        params.insert("username", &username_str);
-        params.insert("password", &password_str);
-
-        let response = self
-            .client
-            .post(login_endpoint)
-            .json(&params)
-            .send()
-            .await?;
-
-        // If the response is successful, extract the session cookie from the
-        // "set-cookie" header and store it in the client's state.
-        if response.status().is_success() {
-            if let Some(cookie) = response.headers().get("set-cookie") {
-                let cookie_str = cookie.to_str()?.to_string();
-                // Parse expiry time from cookie
-                self.session_cookie = Some(cookie_str);
-                self.extract_cookie_expiry();
-
-                // Store credentials for potential re-login
-                self.username = Some(username_str);
-                self.password = Some(password_str);
+        let mut record = self.request_record(&Method::POST, &login_endpoint);
+        let span = request_span(&record);
+        let started = Instant::now();
+
+        let result = async {
+            let mut params = HashMap::new();
+            params.insert("username", &username_str);
+            params.insert("password", &password_str);
+
+            let response = self
+                .client
+                .post(login_endpoint)
+                .json(&params)
+                .send()
+                .await?;
+            record.status = Some(response.status().as_u16());
+
+            // If the response is successful, extract the session cookie from the
+            // "set-cookie" header and store it in the client's state.
+            if response.status().is_success() {
+                if let Some(cookie) = response.headers().get("set-cookie") {
+                    let cookie_str = cookie.to_str()?.to_string();
+                    // Parse expiry time from cookie
+                    self.session_cookie = Some(cookie_str);
+                    self.extract_cookie_expiry();
+
+                    // Store credentials for potential re-login
+                    self.username = Some(username_str);
+                    self.password = Some(password_str);
+                }
+                Ok(())
+            } else {
+                // If the response is not successful, return an error with the status code.
+                record.error = Some(ErrorKind::Auth);
+                Err(MyError::CustomError(format!(
+                    "Login failed with status: {}",
+                    response.status()
+                )))
             }
-            Ok(())
-        } else {
-            // If the response is not successful, return an error with the status code.
-            Err(MyError::CustomError(format!(
-                "Login failed with status: {}",
-                response.status()
-            )))
         }
+        .instrument(span.clone())
+        .await;
+
+        self.finish_request(&span, record, started, &result);
+        result
     }
 
     /// Checks if the stored session cookie is still valid
@@ -180,41 +235,159 @@ impl XUiClient {
     }
 
     /// Re-authenticates if the session cookie is expired or missing
-    async fn ensure_authenticated(&mut self) -> Result<(), MyError> {
+    ///
+    /// Returns `true` if the client logged in again.
+    async fn ensure_authenticated(&mut self) -> Result<bool, MyError> {
         if !self.is_cookie_valid() {
             if let (Some(username), Some(password)) = (self.username.clone(), self.password.clone())
             {
-                return self.login(&username, &password).await;
+                self.login(&username, &password).await?;
+                return Ok(true);
             } else {
                 return Err(MyError::CustomError(
                     "Session expired and no credentials available for re-login".to_string(),
                 ));
             }
         }
-        Ok(())
+        Ok(false)
     }
 
-    /// Attaches the session cookie to the request if available.
-    async fn with_cookie(
+    /// Sends an authenticated request to the panel and hands the response to `handle`.
+    ///
+    /// The client logs in first if the session is missing or expired, and once more if the
+    /// panel answers `401 Unauthorized` to a session it dropped early. The whole exchange is
+    /// traced in an `xui_request` span and reported to the metrics hook.
+    async fn send<T, F, Fut>(
         &mut self,
-        req: reqwest::RequestBuilder,
-    ) -> Result<reqwest::RequestBuilder, MyError> {
-        // Ensure we have a valid authentication cookie
-        self.ensure_authenticated().await?;
-
-        // Now attach the cookie to the request
-        if let Some(ref cookie) = self.session_cookie {
-            Ok(req.header(COOKIE, cookie))
-        } else {
-            // This should not happen due to ensure_authenticated, but just in case
-            Err(MyError::CustomError(
-                "No session cookie available".to_string(),
-            ))
+        method: Method,
+        endpoint_url: url::Url,
+        body: Option<&serde_json::Value>,
+        handle: F,
+    ) -> Result<T, MyError>
+    where
+        F: FnOnce(Response) -> Fut,
+        Fut: Future<Output = Result<T, MyError>>,
+    {
+        let mut record = self.request_record(&method, &endpoint_url);
+        let span = request_span(&record);
+        let started = Instant::now();
+
+        let result = async {
+            let response = self
+                .send_authenticated(method, endpoint_url, body, &mut record)
+                .await?;
+            record.status = Some(response.status().as_u16());
+            handle(response).await
+        }
+        .instrument(span.clone())
+        .await;
+
+        self.finish_request(&span, record, started, &result);
+        result
+    }
+
+    /// Sends a request with the session cookie, logging in again when needed.
+    async fn send_authenticated(
+        &mut self,
+        method: Method,
+        endpoint_url: url::Url,
+        body: Option<&serde_json::Value>,
+        record: &mut RequestRecord,
+    ) -> Result<Response, MyError> {
+        loop {
+            match self.ensure_authenticated().await {
+                Ok(relogin) => record.relogin |= relogin,
+                Err(err) => {
+                    record.error = Some(ErrorKind::Auth);
+                    return Err(err);
+                }
+            }
+
+            let Some(cookie) = self.session_cookie.clone() else {
+                // This should not happen due to ensure_authenticated, but just in case
+                record.error = Some(ErrorKind::Auth);
+                return Err(MyError::CustomError(
+                    "No session cookie available".to_string(),
+                ));
+            };
+
+            let mut req_builder = self
+                .client
+                .request(method.clone(), endpoint_url.clone())
+                .header(COOKIE, cookie);
+            if let Some(json_body) = body {
+                req_builder = req_builder.json(json_body);
+            }
+
+            let response = req_builder.send().await?;
+            if response.status() == StatusCode::UNAUTHORIZED
+                && record.retries == 0
+                && self.username.is_some()
+                && self.password.is_some()
+            {
+                // The panel dropped the session: forget it and retry once after logging in
+                self.session_cookie = None;
+                self.cookie_expiry = None;
+                record.retries += 1;
+                continue;
+            }
+            return Ok(response);
+        }
+    }
+
+    /// Starts the record of a request to `endpoint_url`.
+    fn request_record(&self, method: &Method, endpoint_url: &url::Url) -> RequestRecord {
+        RequestRecord {
+            host: endpoint_url.host_str().unwrap_or_default().to_string(),
+            method: method.to_string(),
+            endpoint: endpoint_url.path().to_string(),
+            status: None,
+            latency: Duration::ZERO,
+            retries: 0,
+            relogin: false,
+            error: None,
+        }
+    }
+
+    /// Completes the record of a request, fills its span and reports it to the metrics hook.
+    fn finish_request<T>(
+        &self,
+        span: &tracing::Span,
+        mut record: RequestRecord,
+        started: Instant,
+        result: &Result<T, MyError>,
+    ) {
+        record.latency = started.elapsed();
+        if let Err(err) = result {
+            let failed_status = record.status.filter(|status| !(200..300).contains(status));
+            record.error = record.error.or_else(|| {
+                Some(match failed_status {
+                    Some(_) => ErrorKind::Status,
+                    None => ErrorKind::of(err),
+                })
+            });
+        }
+
+        if let Some(status) = record.status {
+            span.record("status", status);
+        }
+        span.record("latency_ms", record.latency.as_millis() as u64);
+        span.record("retries", record.retries);
+        span.record("relogin", record.relogin);
+        match (result, record.error) {
+            (Err(err), Some(kind)) => {
+                tracing::debug!(parent: span, error = %err, kind = %kind, "panel request failed")
+            }
+            _ => tracing::debug!(parent: span, "panel request completed"),
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record(&record);
         }
     }
 
     /// Sends a GET request to the specified endpoint and returns the JSON response.
-    async fn api_get_request(
+    pub(crate) async fn api_get_request(
         &mut self,
         endpoint: impl IntoUrl,
     ) -> Result<serde_json::Value, MyError> {
@@ -223,15 +396,10 @@ impl XUiClient {
             Err(e) => return Err(MyError::ReqwestError(e)),
         };
 
-        let response = self
-            .with_cookie(self.client.get(endpoint_url))
-            .await?
-            .send()
-            .await?;
-
-        let response_as_json = response.json().await?;
-
-        Ok(response_as_json)
+        self.send(Method::GET, endpoint_url, None, |response| async move {
+            Ok(response.json().await?)
+        })
+        .await
     }
 
     /// Retrieves a list of all inbound configurations from the 3X-UI panel.
@@ -266,6 +434,35 @@ impl XUiClient {
         self.api_get_request(inbounds_list_endpoint).await
     }
 
+    /// Retrieves all inbounds and decodes them into typed [`Inbound`] values.
+    ///
+    /// Same request as [`XUiClient::get_inbounds`], with the JSON-encoded `settings`,
+    /// `streamSettings` and `sniffing` fields decoded.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the list of inbounds if successful, or a `MyError` if the request
+    /// failed, the panel reported an error or the response could not be decoded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///     for inbound in client.get_inbounds_typed().await? {
+    ///         println!("{} {} {}", inbound.id, inbound.protocol, inbound.port);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_inbounds_typed(&mut self) -> Result<Vec<Inbound>, MyError> {
+        let response = self.get_inbounds().await?;
+        parse_inbounds(response)
+    }
+
     /// Retrieves the configuration for a specific inbound by its ID.
     ///
     /// This function sends a GET request to fetch details about a specific inbound
@@ -310,6 +507,38 @@ impl XUiClient {
         self.api_get_request(inbound_get_endpoint).await
     }
 
+    /// Retrieves a specific inbound by its ID and decodes it into a typed [`Inbound`].
+    ///
+    /// # Arguments
+    ///
+    /// * `inbound_id` - Any type that can be converted into a u64 representing the ID of the inbound to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the inbound if successful, or a `MyError` if the request failed,
+    /// the panel reported an error or the response could not be decoded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///     let inbound = client.get_inbound_typed(1_u64).await?;
+    ///     println!("Inbound clients: {:?}", inbound.clients()?);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_inbound_typed(
+        &mut self,
+        inbound_id: impl Into<u64>,
+    ) -> Result<Inbound, MyError> {
+        let response = self.get_inbound(inbound_id).await?;
+        ApiResponse::<Inbound>::from_value(response)?.into_result()
+    }
+
     /// Retrieves traffic information for a client identified by their email address.
     ///
     /// This function sends a GET request to fetch traffic statistics for a specific client
@@ -424,17 +653,17 @@ impl XUiClient {
                 Err(err) => return Err(MyError::UrlParseError(err)),
             };
 
-        let response = self
-            .with_cookie(self.client.get(create_backup_endpoint))
-            .await?
-            .send()
-            .await?;
-
-        Ok(response.status().as_u16())
+        self.send(
+            Method::GET,
+            create_backup_endpoint,
+            None,
+            |response| async move { Ok(response.status().as_u16()) },
+        )
+        .await
     }
 
     /// Sends a POST request to the specified endpoint with an optional JSON body and returns the JSON response.
-    async fn api_post_request(
+    pub(crate) async fn api_post_request(
         &mut self,
         endpoint: impl IntoUrl,
         body: Option<&serde_json::Value>,
@@ -444,16 +673,37 @@ impl XUiClient {
             Err(e) => return Err(MyError::ReqwestError(e)),
         };
 
-        let mut req_builder = self.with_cookie(self.client.post(endpoint_url)).await?;
+        self.send(Method::POST, endpoint_url, body, |response| async move {
+            Ok(response.json().await?)
+        })
+        .await
+    }
 
-        if let Some(json_body) = body {
-            req_builder = req_builder.json(json_body);
-        }
+    /// Sends a POST request to an endpoint that may not exist on older panel versions.
+    ///
+    /// Returns `Ok(None)` if the panel answers `404 Not Found`, the JSON response otherwise.
+    pub(crate) async fn api_post_request_optional(
+        &mut self,
+        endpoint: impl IntoUrl,
+        body: Option<&serde_json::Value>,
+    ) -> Result<Option<serde_json::Value>, MyError> {
+        let endpoint_url = match endpoint.into_url() {
+            Ok(endpoint_url) => endpoint_url,
+            Err(e) => return Err(MyError::ReqwestError(e)),
+        };
 
-        let response = req_builder.send().await?;
-        let response_as_json = response.json().await?;
+        self.send(Method::POST, endpoint_url, body, |response| async move {
+            if response.status() == StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+            Ok(Some(response.json().await?))
+        })
+        .await
+    }
 
-        Ok(response_as_json)
+    /// Returns the base URL of the panel.
+    pub fn panel_base_url(&self) -> &url::Url {
+        &self.panel_base_url
     }
 
     /// Retrieves IP records for a client identified by their email address.
@@ -1135,4 +1385,58 @@ impl XUiClient {
         // This endpoint doesn't require a request body
         self.api_post_request(online_clients_endpoint, None).await
     }
+
+    /// Retrieves the status of the server running the panel.
+    ///
+    /// This function sends a POST request to fetch CPU, memory, disk, network and Xray
+    /// process information of the server.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `serde_json::Value` with the server status if successful,
+    /// or a `MyError` if an error occurred.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///
+    ///     let status = client.get_server_status().await?;
+    ///     println!("Server status: {}", status);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_server_status(&mut self) -> Result<serde_json::Value, MyError> {
+        let server_status_endpoint = match self.panel_base_url.join("server/status/") {
+            Ok(server_status_endpoint) => server_status_endpoint,
+            Err(err) => return Err(MyError::UrlParseError(err)),
+        };
+
+        // This endpoint doesn't require a request body
+        self.api_post_request(server_status_endpoint, None).await
+    }
+
+    /// Retrieves the status of the server running the panel as a typed [`ServerStatus`].
+    pub async fn get_server_status_typed(&mut self) -> Result<ServerStatus, MyError> {
+        let response = self.get_server_status().await?;
+        ApiResponse::<ServerStatus>::from_value(response)?.into_result()
+    }
+}
+
+/// Creates the span tracing a request, its outcome fields being recorded on completion.
+fn request_span(record: &RequestRecord) -> tracing::Span {
+    tracing::info_span!(
+        "xui_request",
+        host = %record.host,
+        method = %record.method,
+        endpoint = %record.endpoint,
+        status = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+        retries = tracing::field::Empty,
+        relogin = tracing::field::Empty,
+    )
 }
@@ -0,0 +1,57 @@
+//! Prometheus exporter for 3X-UI panels.
+//!
+//! Usage: `xui-exporter <panels.yaml> [listen address]`
+//!
+//! The panels file is a fleet configuration (see `xui_rs::fleet::FleetConfig`); the listen
+//! address defaults to `0.0.0.0:9617` and can also be set with `XUI_EXPORTER_LISTEN`.
+//!
+//! Each panel gets `XUI_EXPORTER_TIMEOUT` seconds (8 by default, below Prometheus' default
+//! `scrape_timeout` of 10s) to answer; slower panels are reported with `xui_up` 0.
+
+use std::time::Duration;
+
+use tokio::net::TcpListener;
+use xui_rs::errors::MyError;
+use xui_rs::exporter::serve;
+use xui_rs::fleet::Fleet;
+
+const DEFAULT_LISTEN: &str = "0.0.0.0:9617";
+const DEFAULT_TIMEOUT_SECS: f64 = 8.0;
+
+#[tokio::main]
+async fn main() -> Result<(), MyError> {
+    let mut args = std::env::args().skip(1);
+    let Some(config) = args.next() else {
+        eprintln!("Usage: xui-exporter <panels.yaml> [listen address]");
+        std::process::exit(2);
+    };
+    let listen = args
+        .next()
+        .or_else(|| std::env::var("XUI_EXPORTER_LISTEN").ok())
+        .unwrap_or_else(|| DEFAULT_LISTEN.to_string());
+
+    let timeout = match std::env::var("XUI_EXPORTER_TIMEOUT") {
+        Ok(value) => value
+            .parse::<f64>()
+            .ok()
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+            .filter(|timeout| !timeout.is_zero())
+            .ok_or_else(|| {
+                MyError::CustomError(format!(
+                    "Invalid XUI_EXPORTER_TIMEOUT '{}': expected a number of seconds",
+                    value
+                ))
+            })?,
+        Err(_) => Duration::from_secs_f64(DEFAULT_TIMEOUT_SECS),
+    };
+
+    let fleet = Fleet::load(&config)?.timeout(timeout);
+    let listener = TcpListener::bind(&listen).await?;
+    eprintln!(
+        "Serving metrics of {} panel(s) on http://{}/metrics",
+        fleet.names().len(),
+        listen
+    );
+
+    serve(fleet, listener).await
+}
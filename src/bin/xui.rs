@@ -0,0 +1,22 @@
+//! Command-line tool for 3X-UI panels.
+//!
+//! Usage: `xui [--profile <name>] [--output table|json|yaml] <command>`
+//!
+//! Profiles are read from a fleet configuration file (`~/.config/xui/config.yaml` by
+//! default, see `xui_rs::fleet::FleetConfig`); `--url`, `XUI_URL`, `XUI_USERNAME` and
+//! `XUI_PASSWORD` select a panel or supply credentials without storing them in the file.
+
+use clap::Parser;
+use xui_rs::cli::{Cli, run};
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    match run(&cli).await {
+        Ok(output) => print!("{}", output),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
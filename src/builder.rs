@@ -0,0 +1,476 @@
+use serde_json::{Value, json};
+
+use crate::errors::MyError;
+use crate::keys::{self, KeyPair};
+use crate::models::{Client, Inbound, Protocol, default_tag};
+
+/// Default Shadowsocks 2022 method of [`InboundBuilder::shadowsocks_2022`].
+pub const DEFAULT_SHADOWSOCKS_2022_METHOD: &str = "2022-blake3-aes-256-gcm";
+
+/// Fluent builder producing typed inbounds in the layout 3X-UI expects, ready to be sent
+/// with `add_inbound`.
+///
+/// Each constructor fills in the defaults of the panel's own "add inbound" form: sniffing,
+/// generated REALITY/WireGuard keys and short IDs, Shadowsocks 2022 keys and, unless
+/// clients are given, one client with a random email. Setters for a transport or security
+/// layer (e.g. [`InboundBuilder::path`] for WebSocket or [`InboundBuilder::dest`] for
+/// REALITY) only make sense on inbounds using it.
+///
+/// # Example
+///
+/// ```rust
+/// use xui_rs::api::XUiClient;
+/// use xui_rs::builder::InboundBuilder;
+/// use xui_rs::models::Client;
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+///     client.login("admin", "password").await?;
+///
+///     let inbound = InboundBuilder::vless_reality(443)
+///         .remark("reality")
+///         .dest("www.example.com:443")
+///         .server_names(["www.example.com"])
+///         .client(Client {
+///             email: "user@example.com".to_string(),
+///             flow: "xtls-rprx-vision".to_string(),
+///             ..Default::default()
+///         })
+///         .build()?;
+///
+///     client.add_inbound(serde_json::to_value(&inbound)?).await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct InboundBuilder {
+    inbound: Inbound,
+    clients: Vec<Client>,
+}
+
+impl InboundBuilder {
+    fn new(port: u16, protocol: Protocol, settings: Value, stream_settings: Value) -> Self {
+        Self {
+            inbound: Inbound {
+                remark: format!("{}-{}", protocol, port),
+                enable: true,
+                port,
+                protocol,
+                settings,
+                stream_settings,
+                sniffing: json!({
+                    "enabled": true,
+                    "destOverride": ["http", "tls", "quic", "fakedns"],
+                    "metadataOnly": false,
+                    "routeOnly": false
+                }),
+                ..Default::default()
+            },
+            clients: Vec::new(),
+        }
+    }
+
+    /// VLESS over TCP with REALITY, with a generated key pair and short IDs.
+    ///
+    /// The destination defaults to the panel's `yahoo.com:443`; set your own with
+    /// [`InboundBuilder::dest`] and [`InboundBuilder::server_names`].
+    pub fn vless_reality(port: u16) -> Self {
+        let keys = keys::reality_keypair();
+        Self::new(
+            port,
+            Protocol::Vless,
+            json!({"decryption": "none", "fallbacks": []}),
+            json!({
+                "network": "tcp",
+                "security": "reality",
+                "externalProxy": [],
+                "realitySettings": {
+                    "show": false,
+                    "xver": 0,
+                    "dest": "yahoo.com:443",
+                    "serverNames": ["yahoo.com", "www.yahoo.com"],
+                    "privateKey": keys.private_key,
+                    "minClient": "",
+                    "maxClient": "",
+                    "maxTimediff": 0,
+                    "shortIds": [keys::short_id(8), keys::short_id(16)],
+                    "settings": {
+                        "publicKey": keys.public_key,
+                        "fingerprint": "chrome",
+                        "serverName": "",
+                        "spiderX": "/"
+                    }
+                },
+                "tcpSettings": {"acceptProxyProtocol": false, "header": {"type": "none"}}
+            }),
+        )
+    }
+
+    /// VMess over WebSocket with TLS. Set the certificate with [`InboundBuilder::certificate`].
+    pub fn vmess_ws_tls(port: u16) -> Self {
+        Self::new(
+            port,
+            Protocol::Vmess,
+            json!({}),
+            json!({
+                "network": "ws",
+                "security": "tls",
+                "externalProxy": [],
+                "tlsSettings": tls_settings(),
+                "wsSettings": {"acceptProxyProtocol": false, "path": "/", "headers": {}}
+            }),
+        )
+    }
+
+    /// Trojan over gRPC with TLS. Set the certificate with [`InboundBuilder::certificate`].
+    pub fn trojan_grpc_tls(port: u16) -> Self {
+        Self::new(
+            port,
+            Protocol::Trojan,
+            json!({"fallbacks": []}),
+            json!({
+                "network": "grpc",
+                "security": "tls",
+                "externalProxy": [],
+                "tlsSettings": tls_settings(),
+                "grpcSettings": {"serviceName": "grpc", "authority": "", "multiMode": false}
+            }),
+        )
+    }
+
+    /// Shadowsocks 2022 ([`DEFAULT_SHADOWSOCKS_2022_METHOD`]) over TCP and UDP, with a
+    /// generated server key; clients get their own generated keys.
+    pub fn shadowsocks_2022(port: u16) -> Self {
+        Self::new(
+            port,
+            Protocol::Shadowsocks,
+            json!({
+                "method": DEFAULT_SHADOWSOCKS_2022_METHOD,
+                "password": keys::shadowsocks_2022_key(DEFAULT_SHADOWSOCKS_2022_METHOD),
+                "network": "tcp,udp"
+            }),
+            json!({
+                "network": "tcp",
+                "security": "none",
+                "externalProxy": [],
+                "tcpSettings": {"acceptProxyProtocol": false, "header": {"type": "none"}}
+            }),
+        )
+    }
+
+    /// SOCKS proxy without authentication; add accounts with [`InboundBuilder::account`].
+    pub fn socks(port: u16) -> Self {
+        Self::new(
+            port,
+            Protocol::Socks,
+            json!({"auth": "noauth", "accounts": [], "udp": false, "ip": "127.0.0.1"}),
+            Value::Null,
+        )
+    }
+
+    /// HTTP proxy without authentication; add accounts with [`InboundBuilder::account`].
+    pub fn http(port: u16) -> Self {
+        Self::new(
+            port,
+            Protocol::Http,
+            json!({"accounts": [], "allowTransparent": false}),
+            Value::Null,
+        )
+    }
+
+    /// Dokodemo-door (port forwarding); set the destination with [`InboundBuilder::target`].
+    pub fn dokodemo_door(port: u16) -> Self {
+        Self::new(
+            port,
+            Protocol::DokodemoDoor,
+            json!({"address": "", "port": 0, "network": "tcp,udp", "followRedirect": false}),
+            Value::Null,
+        )
+    }
+
+    /// WireGuard with a generated server key. Unless peers are added with
+    /// [`InboundBuilder::peer`], one peer with address `10.0.0.2/32` is created.
+    pub fn wireguard(port: u16) -> Self {
+        let mut builder = Self::new(
+            port,
+            Protocol::Wireguard,
+            json!({
+                "mtu": 1420,
+                "secretKey": keys::wireguard_keypair().private_key,
+                "peers": [],
+                "noKernelTun": false
+            }),
+            Value::Null,
+        );
+        builder.inbound.sniffing["enabled"] = json!(false);
+        builder
+    }
+
+    /// Sets the name shown in the panel.
+    pub fn remark(mut self, remark: impl Into<String>) -> Self {
+        self.inbound.remark = remark.into();
+        self
+    }
+
+    /// Sets the listen address; empty (the default) listens on all interfaces.
+    pub fn listen(mut self, listen: impl Into<String>) -> Self {
+        self.inbound.listen = listen.into();
+        self
+    }
+
+    /// Sets whether the inbound is enabled (the default).
+    pub fn enable(mut self, enable: bool) -> Self {
+        self.inbound.enable = enable;
+        self
+    }
+
+    /// Sets the traffic quota of the whole inbound in bytes; `0` (the default) is unlimited.
+    pub fn total(mut self, bytes: u64) -> Self {
+        self.inbound.total = bytes;
+        self
+    }
+
+    /// Sets the expiry of the inbound as a Unix timestamp in milliseconds; `0` never expires.
+    pub fn expiry_time(mut self, expiry_time: i64) -> Self {
+        self.inbound.expiry_time = expiry_time;
+        self
+    }
+
+    /// Enables or disables traffic sniffing (enabled by default, except for WireGuard).
+    pub fn sniffing(mut self, enabled: bool) -> Self {
+        self.inbound.sniffing["enabled"] = json!(enabled);
+        self
+    }
+
+    /// Adds a client. Missing UUIDs and passwords are generated by [`InboundBuilder::build`].
+    pub fn client(mut self, client: Client) -> Self {
+        self.clients.push(client);
+        self
+    }
+
+    /// Adds several clients, see [`InboundBuilder::client`].
+    pub fn clients(mut self, clients: impl IntoIterator<Item = Client>) -> Self {
+        self.clients.extend(clients);
+        self
+    }
+
+    /// Sets the REALITY destination (`host:port`) connections are forwarded to.
+    pub fn dest(mut self, dest: impl Into<String>) -> Self {
+        self.reality()["dest"] = json!(dest.into());
+        self
+    }
+
+    /// Sets the server names (SNI) REALITY accepts; the first one is used in share links.
+    pub fn server_names<I, S>(mut self, server_names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let names: Vec<String> = server_names.into_iter().map(Into::into).collect();
+        self.reality()["serverNames"] = json!(names);
+        self
+    }
+
+    /// Replaces the generated REALITY key pair, e.g. one from [`keys::reality_keypair`].
+    pub fn reality_keys(mut self, keys: KeyPair) -> Self {
+        let reality = self.reality();
+        reality["privateKey"] = json!(keys.private_key);
+        reality["settings"]["publicKey"] = json!(keys.public_key);
+        self
+    }
+
+    /// Replaces the generated REALITY short IDs.
+    pub fn short_ids<I, S>(mut self, short_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let ids: Vec<String> = short_ids.into_iter().map(Into::into).collect();
+        self.reality()["shortIds"] = json!(ids);
+        self
+    }
+
+    /// Sets the uTLS fingerprint advertised to clients (`chrome` by default).
+    pub fn fingerprint(mut self, fingerprint: impl Into<String>) -> Self {
+        let fingerprint = json!(fingerprint.into());
+        match self.inbound.security() {
+            "reality" => self.reality()["settings"]["fingerprint"] = fingerprint,
+            _ => {
+                self.inbound.stream_settings["tlsSettings"]["settings"]["fingerprint"] = fingerprint
+            }
+        }
+        self
+    }
+
+    /// Sets the TLS server name (SNI).
+    pub fn server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.inbound.stream_settings["tlsSettings"]["serverName"] = json!(server_name.into());
+        self
+    }
+
+    /// Sets the paths of the TLS certificate and key on the panel's server.
+    pub fn certificate(
+        mut self,
+        certificate_file: impl Into<String>,
+        key_file: impl Into<String>,
+    ) -> Self {
+        self.inbound.stream_settings["tlsSettings"]["certificates"] = json!([{
+            "certificateFile": certificate_file.into(),
+            "keyFile": key_file.into(),
+            "ocspStapling": 3600
+        }]);
+        self
+    }
+
+    /// Sets the TLS ALPN protocols (`h2` and `http/1.1` by default).
+    pub fn alpn<I, S>(mut self, alpn: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let alpn: Vec<String> = alpn.into_iter().map(Into::into).collect();
+        self.inbound.stream_settings["tlsSettings"]["alpn"] = json!(alpn);
+        self
+    }
+
+    /// Sets the WebSocket path (`/` by default).
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.inbound.stream_settings["wsSettings"]["path"] = json!(path.into());
+        self
+    }
+
+    /// Sets the WebSocket `Host` header.
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.inbound.stream_settings["wsSettings"]["headers"]["Host"] = json!(host.into());
+        self
+    }
+
+    /// Sets the gRPC service name (`grpc` by default).
+    pub fn service_name(mut self, service_name: impl Into<String>) -> Self {
+        self.inbound.stream_settings["grpcSettings"]["serviceName"] = json!(service_name.into());
+        self
+    }
+
+    /// Changes the Shadowsocks 2022 method, generating a server key of the matching length.
+    pub fn method(mut self, method: impl Into<String>) -> Self {
+        let method = method.into();
+        self.inbound.settings["password"] = json!(keys::shadowsocks_2022_key(&method));
+        self.inbound.settings["method"] = json!(method);
+        self
+    }
+
+    /// Adds a SOCKS/HTTP account, enabling password authentication for SOCKS.
+    pub fn account(mut self, user: impl Into<String>, pass: impl Into<String>) -> Self {
+        let account = json!({"user": user.into(), "pass": pass.into()});
+        match self.inbound.settings["accounts"].as_array_mut() {
+            Some(accounts) => accounts.push(account),
+            None => self.inbound.settings["accounts"] = json!([account]),
+        }
+        if self.inbound.protocol == Protocol::Socks {
+            self.inbound.settings["auth"] = json!("password");
+        }
+        self
+    }
+
+    /// Enables UDP for a SOCKS inbound.
+    pub fn udp(mut self, udp: bool) -> Self {
+        self.inbound.settings["udp"] = json!(udp);
+        self
+    }
+
+    /// Sets the address and port a dokodemo-door inbound forwards to.
+    pub fn target(mut self, address: impl Into<String>, port: u16) -> Self {
+        self.inbound.settings["address"] = json!(address.into());
+        self.inbound.settings["port"] = json!(port);
+        self
+    }
+
+    /// Adds a WireGuard peer with a generated key pair and the given allowed IPs.
+    pub fn peer<I, S>(mut self, allowed_ips: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let allowed_ips: Vec<String> = allowed_ips.into_iter().map(Into::into).collect();
+        let keys = keys::wireguard_keypair();
+        let peer = json!({
+            "privateKey": keys.private_key,
+            "publicKey": keys.public_key,
+            "allowedIPs": allowed_ips,
+            "keepAlive": 0
+        });
+        match self.inbound.settings["peers"].as_array_mut() {
+            Some(peers) => peers.push(peer),
+            None => self.inbound.settings["peers"] = json!([peer]),
+        }
+        self
+    }
+
+    /// Builds the inbound, generating missing client credentials and the default client
+    /// or WireGuard peer.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `MyError` if the clients cannot be encoded into the inbound settings.
+    pub fn build(mut self) -> Result<Inbound, MyError> {
+        if self.inbound.protocol == Protocol::Wireguard
+            && self.inbound.settings["peers"]
+                .as_array()
+                .is_none_or(|peers| peers.is_empty())
+        {
+            self = self.peer(["10.0.0.2/32"]);
+        }
+
+        let protocol = self.inbound.protocol.clone();
+        if protocol.has_clients() {
+            let mut clients = std::mem::take(&mut self.clients);
+            if clients.is_empty() {
+                clients.push(self.default_client());
+            }
+
+            for client in &mut clients {
+                client.generate_credentials(&protocol);
+                if protocol == Protocol::Shadowsocks && client.password.is_none() {
+                    let method = self.inbound.settings["method"].as_str().unwrap_or_default();
+                    client.password = Some(keys::shadowsocks_2022_key(method));
+                    client.method = Some(String::new());
+                }
+            }
+            self.inbound.set_clients(&clients)?;
+        }
+
+        self.inbound.tag = default_tag(&self.inbound.listen, self.inbound.port);
+
+        Ok(self.inbound)
+    }
+
+    /// Returns the client created when none is given, like the panel's form does.
+    fn default_client(&self) -> Client {
+        let mut client = Client {
+            email: keys::short_id(8),
+            sub_id: keys::short_id(16),
+            ..Default::default()
+        };
+        if self.inbound.protocol == Protocol::Vless && self.inbound.security() == "reality" {
+            client.flow = "xtls-rprx-vision".to_string();
+        }
+        client
+    }
+
+    fn reality(&mut self) -> &mut Value {
+        &mut self.inbound.stream_settings["realitySettings"]
+    }
+}
+
+fn tls_settings() -> Value {
+    json!({
+        "serverName": "",
+        "minVersion": "1.2",
+        "maxVersion": "1.3",
+        "cipherSuites": "",
+        "rejectUnknownSni": false,
+        "certificates": [{"certificateFile": "", "keyFile": "", "ocspStapling": 3600}],
+        "alpn": ["h2", "http/1.1"],
+        "settings": {"allowInsecure": false, "fingerprint": "chrome"}
+    })
+}
@@ -0,0 +1,250 @@
+use futures::stream::{self, StreamExt};
+
+use crate::api::XUiClient;
+use crate::clients::ClientPatch;
+use crate::errors::MyError;
+use crate::models::{Client, Inbound, ensure_success};
+
+/// Default number of inbounds processed concurrently by a [`BulkOperation`].
+pub const DEFAULT_BULK_CONCURRENCY: usize = 4;
+
+/// What a [`BulkOperation`] does to each selected client.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkAction {
+    Enable,
+    Disable,
+    Delete,
+    ResetTraffic,
+    Patch(ClientPatch),
+}
+
+/// Outcome of a bulk action on one client.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkOutcome {
+    /// Dry run: the client was selected but nothing was sent.
+    Planned,
+    Done,
+    Failed(String),
+}
+
+/// Result of a bulk action on one client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkItem {
+    pub inbound_id: u64,
+    pub email: String,
+    pub outcome: BulkOutcome,
+}
+
+/// Itemised report of a [`BulkOperation`], in inbound and client order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BulkReport {
+    pub dry_run: bool,
+    pub items: Vec<BulkItem>,
+}
+
+impl BulkReport {
+    /// Returns the items the action succeeded on.
+    pub fn succeeded(&self) -> Vec<&BulkItem> {
+        self.items
+            .iter()
+            .filter(|item| item.outcome == BulkOutcome::Done)
+            .collect()
+    }
+
+    /// Returns the items the action failed on.
+    pub fn failed(&self) -> Vec<&BulkItem> {
+        self.items
+            .iter()
+            .filter(|item| matches!(item.outcome, BulkOutcome::Failed(_)))
+            .collect()
+    }
+}
+
+type Selector = Box<dyn Fn(&Inbound, &Client) -> bool + Send + Sync>;
+
+/// Applies an action to every client matching a selector, across all inbounds.
+///
+/// Inbounds are processed concurrently (up to `concurrency` at a time), while the
+/// clients of one inbound are processed sequentially: the panel rewrites the whole
+/// inbound on each client change, so concurrent changes to one inbound could be lost.
+///
+/// # Example
+///
+/// ```rust
+/// use xui_rs::api::XUiClient;
+/// use xui_rs::bulk::{BulkAction, BulkOperation};
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+///     client.login("admin", "password").await?;
+///
+///     let report = BulkOperation::new(
+///         |_, c| c.comment == "trial",
+///         BulkAction::Disable,
+///     )
+///     .concurrency(8)
+///     .dry_run(true)
+///     .run(&mut client)
+///     .await?;
+///
+///     for item in &report.items {
+///         println!("{} (inbound {}): {:?}", item.email, item.inbound_id, item.outcome);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct BulkOperation {
+    selector: Selector,
+    action: BulkAction,
+    concurrency: usize,
+    dry_run: bool,
+}
+
+impl BulkOperation {
+    /// Creates an operation applying `action` to the clients for which `selector` holds.
+    pub fn new<F>(selector: F, action: BulkAction) -> Self
+    where
+        F: Fn(&Inbound, &Client) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            selector: Box::new(selector),
+            action,
+            concurrency: DEFAULT_BULK_CONCURRENCY,
+            dry_run: false,
+        }
+    }
+
+    /// Sets how many inbounds are processed at the same time (at least 1).
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// When enabled, only reports the selected clients without changing anything.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Selects the clients from the panel's current inbounds and applies the action.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `MyError` only if the inbounds cannot be fetched or decoded; failures of
+    /// individual clients are reported in the [`BulkReport`].
+    pub async fn run(&self, client: &mut XUiClient) -> Result<BulkReport, MyError> {
+        let inbounds = client.get_inbounds_typed().await?;
+
+        let mut groups: Vec<(Inbound, Vec<Client>)> = Vec::new();
+        for inbound in inbounds {
+            let selected: Vec<Client> = inbound
+                .clients()?
+                .into_iter()
+                .filter(|c| (self.selector)(&inbound, c))
+                .collect();
+            if !selected.is_empty() {
+                groups.push((inbound, selected));
+            }
+        }
+
+        if self.dry_run {
+            let items = groups
+                .into_iter()
+                .flat_map(|(inbound, clients)| {
+                    clients.into_iter().map(move |c| BulkItem {
+                        inbound_id: inbound.id,
+                        email: c.email,
+                        outcome: BulkOutcome::Planned,
+                    })
+                })
+                .collect();
+            return Ok(BulkReport {
+                dry_run: true,
+                items,
+            });
+        }
+
+        let mut results: Vec<(usize, Vec<BulkItem>)> = stream::iter(groups.into_iter().enumerate())
+            .map(|(index, (inbound, clients))| {
+                let mut panel = client.clone();
+                async move {
+                    let mut items = Vec::with_capacity(clients.len());
+                    for c in clients {
+                        let outcome = match self.apply(&mut panel, &inbound, &c).await {
+                            Ok(()) => BulkOutcome::Done,
+                            Err(e) => BulkOutcome::Failed(e.to_string()),
+                        };
+                        items.push(BulkItem {
+                            inbound_id: inbound.id,
+                            email: c.email,
+                            outcome,
+                        });
+                    }
+                    (index, items)
+                }
+            })
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+
+        results.sort_by_key(|(index, _)| *index);
+
+        Ok(BulkReport {
+            dry_run: false,
+            items: results.into_iter().flat_map(|(_, items)| items).collect(),
+        })
+    }
+
+    /// Applies the action to a single client.
+    async fn apply(
+        &self,
+        panel: &mut XUiClient,
+        inbound: &Inbound,
+        client: &Client,
+    ) -> Result<(), MyError> {
+        let key = client.key(&inbound.protocol).ok_or_else(|| {
+            MyError::CustomError(format!(
+                "Client '{}' has no key for protocol '{}'",
+                client.email, inbound.protocol
+            ))
+        })?;
+
+        let response = match &self.action {
+            BulkAction::Delete => panel.delete_client(inbound.id, key).await?,
+            BulkAction::ResetTraffic => {
+                panel
+                    .reset_client_traffic(inbound.id, client.email.clone())
+                    .await?
+            }
+            BulkAction::Enable => {
+                update_with(panel, inbound.id, key, client, |c| c.enable = true).await?
+            }
+            BulkAction::Disable => {
+                update_with(panel, inbound.id, key, client, |c| c.enable = false).await?
+            }
+            BulkAction::Patch(patch) => {
+                update_with(panel, inbound.id, key, client, |c| patch.apply(c)).await?
+            }
+        };
+
+        ensure_success(&response)
+    }
+}
+
+/// Sends `client` back to the panel after applying `modify` to a copy of it.
+async fn update_with<F>(
+    panel: &mut XUiClient,
+    inbound_id: u64,
+    key: &str,
+    client: &Client,
+    modify: F,
+) -> Result<serde_json::Value, MyError>
+where
+    F: FnOnce(&mut Client),
+{
+    let mut updated = client.clone();
+    modify(&mut updated);
+    panel
+        .update_client(key, inbound_id, serde_json::to_value(&updated)?)
+        .await
+}
@@ -0,0 +1,837 @@
+use std::fmt::Write as _;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::api::XUiClient;
+use crate::clients::ClientPatch;
+use crate::errors::MyError;
+use crate::fleet::{FleetConfig, PanelConfig};
+use crate::keys;
+use crate::models::{ApiResponse, Client, ClientTraffic, Inbound, Protocol, ensure_success};
+use crate::online::parse_online_clients;
+use crate::ops::{MILLIS_PER_DAY, now_millis};
+
+/// Profile used when the configuration holds several panels and none is selected.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Bytes in one of the panel's "GB" (GiB).
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// Command line of the `xui` binary.
+#[derive(Debug, Parser)]
+#[command(
+    name = "xui",
+    version,
+    about = "Manage a 3X-UI panel from the command line"
+)]
+pub struct Cli {
+    #[command(flatten)]
+    pub panel: PanelArgs,
+    /// Output format.
+    #[arg(short, long, value_enum, global = true, default_value_t = OutputFormat::Table)]
+    pub output: OutputFormat,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Selection of the panel and its credentials.
+///
+/// Profiles are the panels of a fleet configuration file (see [`FleetConfig`]), whose
+/// credentials may be left out and given through `XUI_USERNAME` and `XUI_PASSWORD`.
+#[derive(Debug, Clone, Default, Args)]
+#[command(next_help_heading = "Panel")]
+pub struct PanelArgs {
+    /// Configuration file with the panel profiles [default: ~/.config/xui/config.yaml].
+    #[arg(long, env = "XUI_CONFIG", global = true)]
+    pub config: Option<PathBuf>,
+    /// Profile to use, by panel name.
+    #[arg(short, long, env = "XUI_PROFILE", global = true)]
+    pub profile: Option<String>,
+    /// URL of the panel, used instead of a profile.
+    #[arg(long, env = "XUI_URL", global = true)]
+    pub url: Option<String>,
+    /// Username, overriding the one of the profile.
+    #[arg(long, env = "XUI_USERNAME", global = true)]
+    pub username: Option<String>,
+    /// Password, overriding the one of the profile. Prefer the environment variable.
+    #[arg(long, env = "XUI_PASSWORD", hide_env_values = true, global = true)]
+    pub password: Option<String>,
+}
+
+impl PanelArgs {
+    /// Returns the panel to connect to, with its credentials.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `MyError` if no panel is configured, the profile does not exist or
+    /// credentials are missing.
+    pub fn resolve(&self) -> Result<PanelConfig, MyError> {
+        let mut panel = match &self.url {
+            Some(url) => PanelConfig {
+                name: self.profile.clone().unwrap_or_default(),
+                url: url.clone(),
+                username: String::new(),
+                password: String::new(),
+            },
+            None => {
+                let path = self
+                    .config
+                    .clone()
+                    .or_else(default_config_path)
+                    .ok_or_else(|| {
+                        MyError::CustomError(
+                            "No configuration file: pass --url or --config".to_string(),
+                        )
+                    })?;
+                let config = FleetConfig::load(&path).map_err(|e| {
+                    MyError::CustomError(format!(
+                        "Cannot load the configuration {} ({}): pass --url or --config",
+                        path.display(),
+                        e
+                    ))
+                })?;
+                select_profile(&config, self.profile.as_deref())?
+            }
+        };
+
+        if let Some(username) = &self.username {
+            panel.username = username.clone();
+        }
+        if let Some(password) = &self.password {
+            panel.password = password.clone();
+        }
+        if panel.username.is_empty() || panel.password.is_empty() {
+            return Err(MyError::CustomError(format!(
+                "No credentials for panel {}: set XUI_USERNAME and XUI_PASSWORD",
+                panel.url
+            )));
+        }
+        Ok(panel)
+    }
+}
+
+/// Returns `$XDG_CONFIG_HOME/xui/config.yaml`, or `~/.config/xui/config.yaml`.
+pub fn default_config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_home.join("xui").join("config.yaml"))
+}
+
+/// Selects a profile of the configuration: the named one, else the only panel, else the
+/// panel named [`DEFAULT_PROFILE`].
+pub fn select_profile(config: &FleetConfig, profile: Option<&str>) -> Result<PanelConfig, MyError> {
+    let names = || {
+        config
+            .panels
+            .iter()
+            .map(|panel| panel.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    if config.panels.is_empty() {
+        return Err(MyError::CustomError(
+            "No panel profile is configured".to_string(),
+        ));
+    }
+
+    let found = match profile {
+        Some(name) => config.panels.iter().find(|panel| panel.name == name),
+        None if config.panels.len() == 1 => config.panels.first(),
+        None => config
+            .panels
+            .iter()
+            .find(|panel| panel.name == DEFAULT_PROFILE),
+    };
+    found.cloned().ok_or_else(|| match profile {
+        Some(name) => MyError::CustomError(format!(
+            "Unknown profile '{}', available: {}",
+            name,
+            names()
+        )),
+        None => MyError::CustomError(format!(
+            "Several profiles are configured ({}): select one with --profile or XUI_PROFILE",
+            names()
+        )),
+    })
+}
+
+/// Format of the command output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable tables.
+    Table,
+    Json,
+    Yaml,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// List, show, add, update or delete inbounds.
+    #[command(subcommand)]
+    Inbounds(InboundsCommand),
+    /// Add, update, delete, find clients or reset their traffic.
+    #[command(subcommand)]
+    Clients(ClientsCommand),
+    /// Show the traffic counters of a client.
+    Traffic { email: String },
+    /// List the clients online.
+    Onlines,
+    /// List the IPs a client connected from.
+    Ips {
+        email: String,
+        /// Clear the IP records instead.
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Trigger a backup of the panel.
+    Backup,
+    /// Show the status of the server.
+    Status,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum InboundsCommand {
+    /// List the inbounds.
+    List,
+    /// Show an inbound and its clients.
+    Get { id: u64 },
+    /// Add an inbound read from a JSON or YAML file (`-` for stdin).
+    Add { file: PathBuf },
+    /// Replace an inbound with one read from a JSON or YAML file (`-` for stdin).
+    Update { id: u64, file: PathBuf },
+    /// Delete an inbound.
+    Delete { id: u64 },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ClientsCommand {
+    /// Add a client to an inbound, generating its credentials.
+    Add {
+        inbound_id: u64,
+        email: String,
+        #[command(flatten)]
+        fields: ClientFields,
+    },
+    /// Change the given fields of a client.
+    Update {
+        inbound_id: u64,
+        email: String,
+        #[command(flatten)]
+        fields: ClientFields,
+    },
+    /// Delete a client.
+    Delete { inbound_id: u64, email: String },
+    /// Find the inbound of a client by email.
+    Find { email: String },
+    /// Reset the traffic counters of a client.
+    Reset { inbound_id: u64, email: String },
+}
+
+/// Client fields settable from the command line.
+#[derive(Debug, Clone, Default, Args)]
+pub struct ClientFields {
+    /// Enable or disable the client.
+    #[arg(long)]
+    pub enable: Option<bool>,
+    /// Traffic quota in GB, 0 for unlimited.
+    #[arg(long)]
+    pub quota_gb: Option<f64>,
+    /// Days until expiry, counted from now, 0 for never.
+    #[arg(long)]
+    pub expiry_days: Option<u32>,
+    /// Maximum number of IPs, 0 for unlimited.
+    #[arg(long)]
+    pub limit_ip: Option<u32>,
+    /// Flow of VLESS clients, e.g. `xtls-rprx-vision`.
+    #[arg(long)]
+    pub flow: Option<String>,
+    /// Subscription ID.
+    #[arg(long)]
+    pub sub_id: Option<String>,
+    #[arg(long)]
+    pub comment: Option<String>,
+}
+
+impl ClientFields {
+    /// Returns the patch setting the given fields, expiries counted from `now`.
+    pub fn patch(&self, now: i64) -> ClientPatch {
+        ClientPatch {
+            enable: self.enable,
+            total_gb: self.quota_gb.map(|gb| (gb.max(0.0) * BYTES_PER_GB) as u64),
+            expiry_time: self.expiry_days.map(|days| match days {
+                0 => 0,
+                days => now + i64::from(days) * MILLIS_PER_DAY,
+            }),
+            limit_ip: self.limit_ip,
+            flow: self.flow.clone(),
+            sub_id: self.sub_id.clone(),
+            comment: self.comment.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Result of a command: the data for JSON and YAML output and its table rendering.
+struct Output {
+    value: Value,
+    text: String,
+}
+
+impl Output {
+    fn new(value: impl Serialize, text: impl Into<String>) -> Result<Self, MyError> {
+        Ok(Self {
+            value: serde_json::to_value(value)?,
+            text: text.into(),
+        })
+    }
+
+    /// Output of a panel response only reporting a status.
+    fn response(response: Value) -> Result<Self, MyError> {
+        ensure_success(&response)?;
+        let msg = match response["msg"].as_str() {
+            Some(msg) if !msg.is_empty() => msg.to_string(),
+            _ => "Done".to_string(),
+        };
+        Ok(Self {
+            value: response,
+            text: format!("{}\n", msg),
+        })
+    }
+
+    fn render(self, format: OutputFormat) -> Result<String, MyError> {
+        match format {
+            OutputFormat::Table => Ok(self.text),
+            OutputFormat::Json => Ok(format!("{}\n", serde_json::to_string_pretty(&self.value)?)),
+            OutputFormat::Yaml => serde_yaml::to_string(&self.value)
+                .map_err(|e| MyError::CustomError(format!("Cannot render YAML: {}", e))),
+        }
+    }
+}
+
+/// A table with left-aligned columns.
+struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn new(headers: &[&'static str]) -> Self {
+        Self {
+            headers: headers.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+    }
+
+    fn render(&self) -> String {
+        let mut widths: Vec<usize> = self.headers.iter().map(|header| header.len()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut out = String::new();
+        let headers = self.headers.iter().map(|header| header.to_string());
+        for cells in std::iter::once(headers.collect::<Vec<_>>()).chain(self.rows.clone()) {
+            let mut line = String::new();
+            for (cell, width) in cells.iter().zip(&widths) {
+                let _ = write!(line, "{:<width$}  ", cell, width = width);
+            }
+            let _ = writeln!(out, "{}", line.trim_end());
+        }
+        out
+    }
+}
+
+/// Renders label/value pairs one per line, values aligned.
+fn fields(pairs: &[(&str, String)]) -> String {
+    let width = pairs
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let mut out = String::new();
+    for (label, value) in pairs {
+        let _ = writeln!(
+            out,
+            "{:<width$} {}",
+            format!("{}:", label),
+            value,
+            width = width
+        );
+    }
+    out
+}
+
+/// Runs the command against the selected panel and returns its rendered output.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Parser;
+/// use xui_rs::cli::{Cli, run};
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let cli = Cli::parse_from(["xui", "--profile", "de-1", "inbounds", "list"]);
+///     print!("{}", run(&cli).await?);
+///     Ok(())
+/// }
+/// ```
+pub async fn run(cli: &Cli) -> Result<String, MyError> {
+    let panel = cli.panel.resolve()?;
+    let mut client =
+        XUiClient::new(panel.url.as_str())?.with_credentials(&panel.username, &panel.password);
+
+    execute(&cli.command, &mut client).await?.render(cli.output)
+}
+
+async fn execute(command: &Command, client: &mut XUiClient) -> Result<Output, MyError> {
+    let now = now_millis();
+    match command {
+        Command::Inbounds(command) => inbounds(command, client, now).await,
+        Command::Clients(command) => clients(command, client, now).await,
+        Command::Traffic { email } => {
+            let response = client.get_client_traffic_by_email(email.as_str()).await?;
+            let traffic = ApiResponse::<ClientTraffic>::from_value(response)?
+                .into_optional()?
+                .ok_or_else(|| MyError::CustomError(format!("Client '{}' not found", email)))?;
+
+            let mut table = Table::new(&[
+                "EMAIL",
+                "INBOUND",
+                "ENABLED",
+                "UP",
+                "DOWN",
+                "QUOTA",
+                "EXPIRY",
+                "LAST ONLINE",
+            ]);
+            table.row(vec![
+                traffic.email.clone(),
+                traffic.inbound_id.to_string(),
+                traffic.enable.to_string(),
+                format_bytes(traffic.up),
+                format_bytes(traffic.down),
+                format_quota(traffic.total),
+                format_expiry(traffic.expiry_time, now),
+                format_ago(traffic.last_online, now),
+            ]);
+            Output::new(&traffic, table.render())
+        }
+        Command::Onlines => {
+            let online = parse_online_clients(client.get_online_clients().await?)?;
+            let mut table = Table::new(&["EMAIL"]);
+            for email in &online {
+                table.row(vec![email.clone()]);
+            }
+            Output::new(&online, table.render())
+        }
+        Command::Ips { email, clear: true } => {
+            Output::response(client.clear_client_ips(email.as_str()).await?)
+        }
+        Command::Ips {
+            email,
+            clear: false,
+        } => {
+            let ips = client.get_client_ips_typed(email.as_str()).await?;
+            let mut table = Table::new(&["IP", "LAST SEEN"]);
+            for ip in &ips {
+                table.row(vec![
+                    ip.ip.clone(),
+                    format_ago(ip.last_seen.unwrap_or_default(), now),
+                ]);
+            }
+            Output::new(&ips, table.render())
+        }
+        Command::Backup => {
+            let status = client.get_backup().await?;
+            if !(200..300).contains(&status) {
+                return Err(MyError::CustomError(format!(
+                    "Backup failed with status: {}",
+                    status
+                )));
+            }
+            Output::new(json!({ "status": status }), "Backup requested\n")
+        }
+        Command::Status => {
+            let status = client.get_server_status_typed().await?;
+            let xray = match status.xray.version.as_str() {
+                "" => status.xray.state.clone(),
+                version => format!("{} ({})", status.xray.state, version),
+            };
+            let text = fields(&[
+                (
+                    "CPU",
+                    format!("{:.1}% of {} cores", status.cpu, status.cpu_cores),
+                ),
+                ("Memory", format_usage(status.mem.current, status.mem.total)),
+                ("Swap", format_usage(status.swap.current, status.swap.total)),
+                ("Disk", format_usage(status.disk.current, status.disk.total)),
+                (
+                    "Load",
+                    status
+                        .loads
+                        .iter()
+                        .map(|load| format!("{:.2}", load))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ),
+                ("Uptime", format_duration(status.uptime)),
+                ("Xray", xray),
+                (
+                    "Connections",
+                    format!("{} TCP, {} UDP", status.tcp_count, status.udp_count),
+                ),
+                (
+                    "Network",
+                    format!(
+                        "up {}/s, down {}/s",
+                        format_bytes(status.net_io.up),
+                        format_bytes(status.net_io.down)
+                    ),
+                ),
+                (
+                    "Public IP",
+                    [
+                        status.public_ip.ipv4.as_str(),
+                        status.public_ip.ipv6.as_str(),
+                    ]
+                    .iter()
+                    .filter(|ip| !ip.is_empty())
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                ),
+            ]);
+            Output::new(&status, text)
+        }
+    }
+}
+
+async fn inbounds(
+    command: &InboundsCommand,
+    client: &mut XUiClient,
+    now: i64,
+) -> Result<Output, MyError> {
+    match command {
+        InboundsCommand::List => {
+            let inbounds = client.get_inbounds_typed().await?;
+            Output::new(&inbounds, inbounds_table(&inbounds, now).render())
+        }
+        InboundsCommand::Get { id } => {
+            let inbound = client.get_inbound_typed(*id).await?;
+            let text = format!(
+                "{}\n{}",
+                inbounds_table(std::slice::from_ref(&inbound), now).render(),
+                clients_table(&inbound, now)?.render()
+            );
+            Output::new(&inbound, text)
+        }
+        InboundsCommand::Add { file } => {
+            let inbound = read_inbound(file)?;
+            check_inbound(client, &inbound).await?;
+            Output::response(client.add_inbound(serde_json::to_value(&inbound)?).await?)
+        }
+        InboundsCommand::Update { id, file } => {
+            let mut inbound = read_inbound(file)?;
+            inbound.id = *id;
+            check_inbound(client, &inbound).await?;
+            Output::response(
+                client
+                    .update_inbound(*id, serde_json::to_value(&inbound)?)
+                    .await?,
+            )
+        }
+        InboundsCommand::Delete { id } => Output::response(client.delete_inbound(*id).await?),
+    }
+}
+
+async fn clients(
+    command: &ClientsCommand,
+    client: &mut XUiClient,
+    now: i64,
+) -> Result<Output, MyError> {
+    match command {
+        ClientsCommand::Add {
+            inbound_id,
+            email,
+            fields,
+        } => {
+            let inbound = client.get_inbound_typed(*inbound_id).await?;
+            let mut entry = Client {
+                email: email.clone(),
+                sub_id: keys::short_id(16),
+                ..Default::default()
+            };
+            if inbound.protocol == Protocol::Vless && inbound.security() == "reality" {
+                entry.flow = "xtls-rprx-vision".to_string();
+            }
+            fields.patch(now).apply(&mut entry);
+            entry.generate_credentials(&inbound.protocol);
+            if inbound.protocol == Protocol::Shadowsocks && entry.password.is_none() {
+                let method = inbound.settings["method"].as_str().unwrap_or_default();
+                entry.password = Some(keys::shadowsocks_2022_key(method));
+                entry.method = Some(String::new());
+            }
+
+            let response = client
+                .add_client(*inbound_id, serde_json::to_value(&entry)?)
+                .await?;
+            ensure_success(&response)?;
+            Output::new(
+                &entry,
+                format!("Added client {} to inbound {}\n", entry.email, inbound_id),
+            )
+        }
+        ClientsCommand::Update {
+            inbound_id,
+            email,
+            fields,
+        } => {
+            let patch = fields.patch(now);
+            if patch.is_empty() {
+                return Err(MyError::CustomError(
+                    "Nothing to update: give at least one field".to_string(),
+                ));
+            }
+            let updated = client
+                .patch_client(*inbound_id, email.as_str(), &patch)
+                .await?;
+            Output::new(&updated, format!("Updated client {}\n", updated.email))
+        }
+        ClientsCommand::Delete { inbound_id, email } => Output::response(
+            client
+                .delete_client_by_email(*inbound_id, email.as_str())
+                .await?,
+        ),
+        ClientsCommand::Find { email } => {
+            let found = client
+                .find_client(email.as_str())
+                .await?
+                .ok_or_else(|| MyError::CustomError(format!("Client '{}' not found", email)))?;
+            let traffic = found.traffic.clone().unwrap_or_default();
+            let text = fields(&[
+                ("Email", found.client.email.clone()),
+                (
+                    "Inbound",
+                    format!(
+                        "{} ({}, {} port {})",
+                        found.inbound.id,
+                        found.inbound.remark,
+                        found.inbound.protocol,
+                        found.inbound.port
+                    ),
+                ),
+                (
+                    "Key",
+                    found
+                        .client
+                        .key(&found.inbound.protocol)
+                        .unwrap_or("-")
+                        .to_string(),
+                ),
+                ("Enabled", found.client.enable.to_string()),
+                (
+                    "Used",
+                    format!(
+                        "{} of {}",
+                        format_bytes(traffic.used()),
+                        format_quota(found.client.total_gb)
+                    ),
+                ),
+                ("Expiry", format_expiry(found.client.expiry_time, now)),
+                ("Last online", format_ago(traffic.last_online, now)),
+                ("Sub ID", found.client.sub_id.clone()),
+            ]);
+            Output::new(
+                json!({
+                    "inboundId": found.inbound.id,
+                    "remark": found.inbound.remark,
+                    "protocol": found.inbound.protocol,
+                    "client": found.client,
+                    "traffic": found.traffic,
+                }),
+                text,
+            )
+        }
+        ClientsCommand::Reset { inbound_id, email } => Output::response(
+            client
+                .reset_client_traffic(*inbound_id, email.as_str())
+                .await?,
+        ),
+    }
+}
+
+/// Reads an inbound from a JSON or YAML file, or from stdin for `-`.
+fn read_inbound(path: &Path) -> Result<Inbound, MyError> {
+    let content = if path == Path::new("-") {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        content
+    } else {
+        std::fs::read_to_string(path)?
+    };
+
+    serde_json::from_str(&content).or_else(|_| {
+        serde_yaml::from_str(&content).map_err(|e| {
+            MyError::CustomError(format!("Invalid inbound in {}: {}", path.display(), e))
+        })
+    })
+}
+
+/// Validates an inbound against the panel, failing on errors and printing warnings.
+async fn check_inbound(client: &mut XUiClient, inbound: &Inbound) -> Result<(), MyError> {
+    let report = client.validate_inbound(inbound).await?;
+    for warning in report.warnings() {
+        eprintln!("{}", warning);
+    }
+    if !report.is_valid() {
+        let errors: Vec<String> = report.errors().iter().map(ToString::to_string).collect();
+        return Err(MyError::CustomError(format!(
+            "Invalid inbound:\n{}",
+            errors.join("\n")
+        )));
+    }
+    Ok(())
+}
+
+fn inbounds_table(inbounds: &[Inbound], now: i64) -> Table {
+    let mut table = Table::new(&[
+        "ID",
+        "REMARK",
+        "PROTOCOL",
+        "PORT",
+        "TRANSPORT",
+        "ENABLED",
+        "CLIENTS",
+        "UP",
+        "DOWN",
+        "QUOTA",
+        "EXPIRY",
+    ]);
+    for inbound in inbounds {
+        table.row(vec![
+            inbound.id.to_string(),
+            inbound.remark.clone(),
+            inbound.protocol.to_string(),
+            inbound.port.to_string(),
+            format!("{}/{}", inbound.network(), inbound.security()),
+            inbound.enable.to_string(),
+            inbound.client_stats.len().to_string(),
+            format_bytes(inbound.up),
+            format_bytes(inbound.down),
+            format_quota(inbound.total),
+            format_expiry(inbound.expiry_time, now),
+        ]);
+    }
+    table
+}
+
+fn clients_table(inbound: &Inbound, now: i64) -> Result<Table, MyError> {
+    let mut table = Table::new(&[
+        "EMAIL",
+        "ENABLED",
+        "UP",
+        "DOWN",
+        "QUOTA",
+        "EXPIRY",
+        "LIMIT IP",
+        "LAST ONLINE",
+    ]);
+    for entry in inbound.clients()? {
+        let traffic = inbound
+            .client_traffic(&entry.email)
+            .cloned()
+            .unwrap_or_default();
+        table.row(vec![
+            entry.email.clone(),
+            entry.enable.to_string(),
+            format_bytes(traffic.up),
+            format_bytes(traffic.down),
+            format_quota(entry.total_gb),
+            format_expiry(entry.expiry_time, now),
+            match entry.limit_ip {
+                0 => "-".to_string(),
+                limit => limit.to_string(),
+            },
+            format_ago(traffic.last_online, now),
+        ]);
+    }
+    Ok(table)
+}
+
+/// Formats a byte count with binary units, e.g. `1.50 GiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.2} {}", value, UNITS[unit])
+    }
+}
+
+fn format_quota(bytes: u64) -> String {
+    match bytes {
+        0 => "unlimited".to_string(),
+        bytes => format_bytes(bytes),
+    }
+}
+
+fn format_usage(used: u64, total: u64) -> String {
+    format!("{} / {}", format_bytes(used), format_bytes(total))
+}
+
+/// Formats a duration in seconds as days, hours and minutes, e.g. `3d 4h 5m`.
+pub fn format_duration(seconds: u64) -> String {
+    let (days, hours, minutes) = (
+        seconds / 86_400,
+        seconds % 86_400 / 3600,
+        seconds % 3600 / 60,
+    );
+    match (days, hours) {
+        (0, 0) => format!("{}m", minutes),
+        (0, _) => format!("{}h {}m", hours, minutes),
+        _ => format!("{}d {}h {}m", days, hours, minutes),
+    }
+}
+
+/// Formats a panel expiry (Unix milliseconds, `0` for never, negative for a duration
+/// starting on first use) relative to `now`.
+pub fn format_expiry(expiry_time: i64, now: i64) -> String {
+    match expiry_time {
+        0 => "never".to_string(),
+        expiry if expiry < 0 => format!(
+            "{} after first use",
+            format_duration((-expiry / 1000) as u64)
+        ),
+        expiry if expiry <= now => "expired".to_string(),
+        expiry => format!("in {}", format_duration(((expiry - now) / 1000) as u64)),
+    }
+}
+
+/// Formats a past Unix timestamp in milliseconds relative to `now`, `-` if unset.
+fn format_ago(timestamp: i64, now: i64) -> String {
+    if timestamp <= 0 {
+        return "-".to_string();
+    }
+    format!(
+        "{} ago",
+        format_duration(((now - timestamp).max(0) / 1000) as u64)
+    )
+}
@@ -0,0 +1,638 @@
+use crate::api::XUiClient;
+use crate::errors::MyError;
+use crate::models::{Client, ClientTraffic, Inbound, ensure_success};
+
+/// A client located in one of the panel's inbounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoundClient {
+    /// The inbound the client belongs to.
+    pub inbound: Inbound,
+    /// The full client entry from the inbound's `settings.clients`.
+    pub client: Client,
+    /// The client's traffic counters, if the panel reported them.
+    pub traffic: Option<ClientTraffic>,
+}
+
+/// A partial update of a client: only the fields set to `Some` are changed.
+///
+/// Used with [`XUiClient::patch_client`], which fetches the current client, applies the
+/// patch and sends the complete entry back, so fields not mentioned here keep their value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClientPatch {
+    pub email: Option<String>,
+    pub enable: Option<bool>,
+    /// See [`Client::expiry_time`] for the encoding.
+    pub expiry_time: Option<i64>,
+    /// Traffic quota in bytes; `0` means unlimited.
+    pub total_gb: Option<u64>,
+    pub limit_ip: Option<u32>,
+    pub flow: Option<String>,
+    pub sub_id: Option<String>,
+    pub comment: Option<String>,
+    pub reset: Option<u32>,
+    /// Additional raw fields (e.g. `tgId`) to set on the client.
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ClientPatch {
+    /// Returns `true` if the patch does not change anything.
+    pub fn is_empty(&self) -> bool {
+        *self == ClientPatch::default()
+    }
+
+    /// Applies the patch to a client in place.
+    pub fn apply(&self, client: &mut Client) {
+        if let Some(email) = &self.email {
+            client.email = email.clone();
+        }
+        if let Some(enable) = self.enable {
+            client.enable = enable;
+        }
+        if let Some(expiry_time) = self.expiry_time {
+            client.expiry_time = expiry_time;
+        }
+        if let Some(total_gb) = self.total_gb {
+            client.total_gb = total_gb;
+        }
+        if let Some(limit_ip) = self.limit_ip {
+            client.limit_ip = limit_ip;
+        }
+        if let Some(flow) = &self.flow {
+            client.flow = flow.clone();
+        }
+        if let Some(sub_id) = &self.sub_id {
+            client.sub_id = sub_id.clone();
+        }
+        if let Some(comment) = &self.comment {
+            client.comment = comment.clone();
+        }
+        if let Some(reset) = self.reset {
+            client.reset = reset;
+        }
+        for (key, value) in &self.extra {
+            client.extra.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+/// Number of clients sent per `addClient` request by [`XUiClient::add_clients`].
+pub const DEFAULT_ADD_CHUNK_SIZE: usize = 50;
+
+/// Outcome of adding one client with [`XUiClient::add_clients`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AddClientOutcome {
+    /// The panel accepted the client.
+    Added,
+    /// The email appears earlier in the same batch; the client was not sent.
+    DuplicateInBatch,
+    /// A client with this email already exists in the given inbound; the client was not sent.
+    EmailExists { inbound_id: u64 },
+    /// The panel rejected the client or the request failed.
+    Failed(String),
+}
+
+/// Per-client result of [`XUiClient::add_clients`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddClientResult {
+    pub email: String,
+    pub outcome: AddClientOutcome,
+}
+
+/// Report of a bulk client creation, one result per input client, in input order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AddClientsReport {
+    pub results: Vec<AddClientResult>,
+}
+
+impl AddClientsReport {
+    /// Returns the emails of the clients that were added.
+    pub fn added(&self) -> Vec<&str> {
+        self.results
+            .iter()
+            .filter(|result| result.outcome == AddClientOutcome::Added)
+            .map(|result| result.email.as_str())
+            .collect()
+    }
+
+    /// Returns the results of the clients that were not added.
+    pub fn failed(&self) -> Vec<&AddClientResult> {
+        self.results
+            .iter()
+            .filter(|result| result.outcome != AddClientOutcome::Added)
+            .collect()
+    }
+
+    /// Returns `true` if every client was added.
+    pub fn is_success(&self) -> bool {
+        self.failed().is_empty()
+    }
+}
+
+/// Returns every client of `inbounds` for which `predicate` holds.
+///
+/// This is the building block of the `find_*` lookups; it can be used directly on
+/// inbounds that were already fetched to avoid another request.
+pub fn find_clients<F>(inbounds: &[Inbound], mut predicate: F) -> Result<Vec<FoundClient>, MyError>
+where
+    F: FnMut(&Inbound, &Client) -> bool,
+{
+    let mut found = Vec::new();
+
+    for inbound in inbounds {
+        for client in inbound.clients()? {
+            if predicate(inbound, &client) {
+                found.push(FoundClient {
+                    inbound: inbound.clone(),
+                    traffic: inbound.client_traffic(&client.email).cloned(),
+                    client,
+                });
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+/// Resolves the key the panel uses for the client with `email` in `inbound`.
+fn client_key(inbound: &Inbound, email: &str) -> Result<String, MyError> {
+    let clients = inbound.clients()?;
+    let client = clients
+        .iter()
+        .find(|client| client.email == email)
+        .ok_or_else(|| {
+            MyError::CustomError(format!(
+                "Client '{}' not found in inbound {}",
+                email, inbound.id
+            ))
+        })?;
+
+    match client.key(&inbound.protocol) {
+        Some(key) => Ok(key.to_string()),
+        None => Err(MyError::CustomError(format!(
+            "Client '{}' has no key for protocol '{}'",
+            email, inbound.protocol
+        ))),
+    }
+}
+
+impl XUiClient {
+    /// Finds a client by email across all inbounds.
+    ///
+    /// The API is keyed by inbound ID; this scans the inbound list and decodes every
+    /// inbound's settings to locate the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `email` - Any type that can be converted into a String representing the email address of the client.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `Some(FoundClient)` with the inbound, the client entry and its
+    /// traffic counters, `None` if no client has this email, or a `MyError` if an error occurred.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///
+    ///     if let Some(found) = client.find_client("user@example.com").await? {
+    ///         println!("Inbound {}: {:?}", found.inbound.id, found.traffic);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn find_client(
+        &mut self,
+        email: impl Into<String>,
+    ) -> Result<Option<FoundClient>, MyError> {
+        let email = email.into();
+        let inbounds = self.get_inbounds_typed().await?;
+
+        Ok(find_clients(&inbounds, |_, client| client.email == email)?
+            .into_iter()
+            .next())
+    }
+
+    /// Finds all clients sharing a subscription ID across all inbounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `sub_id` - Any type that can be converted into a String representing the `subId` of the clients.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the matching clients (possibly empty), or a `MyError` if an error occurred.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///
+    ///     for found in client.find_clients_by_sub_id("k8xq2nz7a1b2c3d4").await? {
+    ///         println!("{} in inbound {}", found.client.email, found.inbound.id);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn find_clients_by_sub_id(
+        &mut self,
+        sub_id: impl Into<String>,
+    ) -> Result<Vec<FoundClient>, MyError> {
+        let sub_id = sub_id.into();
+        let inbounds = self.get_inbounds_typed().await?;
+
+        find_clients(&inbounds, |_, client| client.sub_id == sub_id)
+    }
+
+    /// Finds a VMess/VLESS client by its UUID across all inbounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `uuid` - Any type that can be converted into a String representing the UUID of the client.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `Some(FoundClient)` if a client with this UUID exists, `None`
+    /// otherwise, or a `MyError` if an error occurred.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///
+    ///     let found = client.find_client_by_id("d7c06399-a3e3-4007-9109-19012597dd01").await?;
+    ///     println!("Found: {}", found.is_some());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn find_client_by_id(
+        &mut self,
+        uuid: impl Into<String>,
+    ) -> Result<Option<FoundClient>, MyError> {
+        let uuid = uuid.into();
+        let inbounds = self.get_inbounds_typed().await?;
+
+        Ok(find_clients(&inbounds, |_, client| {
+            client
+                .id
+                .as_deref()
+                .is_some_and(|id| id.eq_ignore_ascii_case(&uuid))
+        })?
+        .into_iter()
+        .next())
+    }
+
+    /// Deletes a client from a specific inbound, identified by its email address.
+    ///
+    /// Newer panels expose `delClientByEmail`, which is used when available. On older
+    /// panels (the endpoint answers `404`), the inbound is fetched to resolve the key
+    /// `delClient` expects for the inbound's protocol (UUID, Trojan password or
+    /// Shadowsocks email).
+    ///
+    /// # Arguments
+    ///
+    /// * `inbound_id` - Any type that can be converted into a u64 representing the ID of the inbound.
+    /// * `email` - Any type that can be converted into a String representing the email address of the client to delete.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `serde_json::Value` with the response if successful,
+    /// or a `MyError` if an error occurred.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///
+    ///     let response = client.delete_client_by_email(3_u64, "user@example.com").await?;
+    ///     println!("Delete client response: {}", response);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn delete_client_by_email(
+        &mut self,
+        inbound_id: impl Into<u64>,
+        email: impl Into<String>,
+    ) -> Result<serde_json::Value, MyError> {
+        let id = inbound_id.into();
+        let email = email.into();
+
+        let delete_by_email_endpoint = match self.panel_base_url().join(&format!(
+            "panel/api/inbounds/{}/delClientByEmail/{}/",
+            id, email
+        )) {
+            Ok(delete_by_email_endpoint) => delete_by_email_endpoint,
+            Err(err) => return Err(MyError::UrlParseError(err)),
+        };
+
+        if let Some(response) = self
+            .api_post_request_optional(delete_by_email_endpoint, None)
+            .await?
+        {
+            return Ok(response);
+        }
+
+        // Older panels: resolve the protocol-specific key and use delClient
+        let inbound = self.get_inbound_typed(id).await?;
+        let key = client_key(&inbound, &email)?;
+        self.delete_client(id, key).await
+    }
+
+    /// Replaces a client of a specific inbound, identified by its email address.
+    ///
+    /// The inbound is fetched to resolve the key `updateClient` expects for the inbound's
+    /// protocol (UUID, Trojan password or Shadowsocks email), so callers do not need to
+    /// know it.
+    ///
+    /// # Arguments
+    ///
+    /// * `inbound_id` - Any type that can be converted into a u64 representing the ID of the inbound.
+    /// * `email` - Any type that can be converted into a String representing the current email address of the client.
+    /// * `client` - The complete updated client entry.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `serde_json::Value` with the response if successful,
+    /// or a `MyError` if an error occurred.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///
+    ///     if let Some(mut found) = client.find_client("user@example.com").await? {
+    ///         found.client.limit_ip = 3;
+    ///         client
+    ///             .update_client_by_email(found.inbound.id, "user@example.com", &found.client)
+    ///             .await?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn update_client_by_email(
+        &mut self,
+        inbound_id: impl Into<u64>,
+        email: impl Into<String>,
+        client: &Client,
+    ) -> Result<serde_json::Value, MyError> {
+        let id = inbound_id.into();
+        let email = email.into();
+
+        let inbound = self.get_inbound_typed(id).await?;
+        let key = client_key(&inbound, &email)?;
+        self.update_client(key, id, serde_json::to_value(client)?)
+            .await
+    }
+
+    /// Changes only the given fields of a client, keeping all others.
+    ///
+    /// Performs the read-modify-write `update_client` requires: the inbound is fetched,
+    /// the client located, the patch applied and the complete entry sent back.
+    ///
+    /// # Arguments
+    ///
+    /// * `inbound_id` - Any type that can be converted into a u64 representing the ID of the inbound.
+    /// * `key` - Any type that can be converted into a String representing the client's email, UUID or Trojan password.
+    /// * `patch` - The fields to change.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the updated client if the panel accepted it, or a `MyError`
+    /// if the client was not found, the request failed or the panel reported an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    /// use xui_rs::clients::ClientPatch;
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///
+    ///     let patch = ClientPatch {
+    ///         expiry_time: Some(1767225600000),
+    ///         limit_ip: Some(2),
+    ///         ..Default::default()
+    ///     };
+    ///     let updated = client.patch_client(3_u64, "user@example.com", &patch).await?;
+    ///     println!("New expiry: {}", updated.expiry_time);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn patch_client(
+        &mut self,
+        inbound_id: impl Into<u64>,
+        key: impl Into<String>,
+        patch: &ClientPatch,
+    ) -> Result<Client, MyError> {
+        self.modify_client(inbound_id, key, |client| patch.apply(client))
+            .await
+    }
+
+    /// Fetches the inbound, locates the client by email or key, lets `modify` change it
+    /// and sends the complete entry back with `update_client`.
+    pub(crate) async fn modify_client<F>(
+        &mut self,
+        inbound_id: impl Into<u64>,
+        key: impl Into<String>,
+        modify: F,
+    ) -> Result<Client, MyError>
+    where
+        F: FnOnce(&mut Client),
+    {
+        let id = inbound_id.into();
+        let key = key.into();
+
+        let inbound = self.get_inbound_typed(id).await?;
+        let mut client = inbound
+            .clients()?
+            .into_iter()
+            .find(|client| client.email == key || client.key(&inbound.protocol) == Some(&key))
+            .ok_or_else(|| {
+                MyError::CustomError(format!("Client '{}' not found in inbound {}", key, id))
+            })?;
+
+        // The panel addresses the client by its key before the update
+        let current_key = client_key(&inbound, &client.email)?;
+        modify(&mut client);
+
+        let response = self
+            .update_client(current_key, id, serde_json::to_value(&client)?)
+            .await?;
+        ensure_success(&response)?;
+
+        Ok(client)
+    }
+
+    /// Adds many clients to an inbound, [`DEFAULT_ADD_CHUNK_SIZE`] clients per request.
+    ///
+    /// See [`XUiClient::add_clients_chunked`].
+    pub async fn add_clients(
+        &mut self,
+        inbound_id: impl Into<u64>,
+        clients: Vec<Client>,
+    ) -> Result<AddClientsReport, MyError> {
+        self.add_clients_chunked(inbound_id, clients, DEFAULT_ADD_CHUNK_SIZE)
+            .await
+    }
+
+    /// Adds many clients to an inbound, sending `chunk_size` clients per `addClient` request.
+    ///
+    /// Emails must be unique across the whole panel, so duplicates are detected before
+    /// anything is sent: within the batch and against the clients of every inbound.
+    /// If the panel rejects a chunk, its clients are retried one by one so that the
+    /// report pinpoints the offending entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `inbound_id` - Any type that can be converted into a u64 representing the ID of the inbound to add the clients to.
+    /// * `clients` - The clients to add.
+    /// * `chunk_size` - Maximum number of clients per request (at least 1).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the per-client report, or a `MyError` if the inbounds could
+    /// not be fetched for duplicate detection.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    /// use xui_rs::models::Client;
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///
+    ///     let clients = (1..=200)
+    ///         .map(|n| Client {
+    ///             id: Some(format!("00000000-0000-4000-8000-{:012}", n)),
+    ///             email: format!("employee{}@corp.example.com", n),
+    ///             ..Default::default()
+    ///         })
+    ///         .collect();
+    ///
+    ///     let report = client.add_clients_chunked(3_u64, clients, 100).await?;
+    ///     for failure in report.failed() {
+    ///         println!("{}: {:?}", failure.email, failure.outcome);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn add_clients_chunked(
+        &mut self,
+        inbound_id: impl Into<u64>,
+        clients: Vec<Client>,
+        chunk_size: usize,
+    ) -> Result<AddClientsReport, MyError> {
+        let id = inbound_id.into();
+
+        let mut existing = std::collections::HashMap::new();
+        for inbound in self.get_inbounds_typed().await? {
+            for client in inbound.clients()? {
+                existing.insert(client.email, inbound.id);
+            }
+        }
+
+        let mut outcomes: Vec<Option<AddClientOutcome>> = Vec::with_capacity(clients.len());
+        let mut seen = std::collections::HashSet::new();
+        let mut pending = Vec::new();
+        for (index, client) in clients.iter().enumerate() {
+            let outcome = if let Some(&inbound_id) = existing.get(&client.email) {
+                Some(AddClientOutcome::EmailExists { inbound_id })
+            } else if !seen.insert(client.email.as_str()) {
+                Some(AddClientOutcome::DuplicateInBatch)
+            } else {
+                pending.push(index);
+                None
+            };
+            outcomes.push(outcome);
+        }
+
+        for chunk in pending.chunks(chunk_size.max(1)) {
+            let batch: Vec<&Client> = chunk.iter().map(|&index| &clients[index]).collect();
+
+            match self.add_clients_request(id, &batch).await {
+                Ok(()) => {
+                    for &index in chunk {
+                        outcomes[index] = Some(AddClientOutcome::Added);
+                    }
+                }
+                Err(e) if chunk.len() == 1 => {
+                    outcomes[chunk[0]] = Some(AddClientOutcome::Failed(e.to_string()));
+                }
+                Err(_) => {
+                    // Isolate the rejected clients
+                    for &index in chunk {
+                        let outcome = match self.add_clients_request(id, &[&clients[index]]).await {
+                            Ok(()) => AddClientOutcome::Added,
+                            Err(e) => AddClientOutcome::Failed(e.to_string()),
+                        };
+                        outcomes[index] = Some(outcome);
+                    }
+                }
+            }
+        }
+
+        let results = clients
+            .into_iter()
+            .zip(outcomes)
+            .map(|(client, outcome)| AddClientResult {
+                email: client.email,
+                outcome: outcome.unwrap_or(AddClientOutcome::Failed("Not sent".to_string())),
+            })
+            .collect();
+
+        Ok(AddClientsReport { results })
+    }
+
+    /// Sends one `addClient` request carrying several clients.
+    async fn add_clients_request(
+        &mut self,
+        inbound_id: u64,
+        clients: &[&Client],
+    ) -> Result<(), MyError> {
+        let add_client_endpoint = match self.panel_base_url().join("panel/api/inbounds/addClient/")
+        {
+            Ok(add_client_endpoint) => add_client_endpoint,
+            Err(err) => return Err(MyError::UrlParseError(err)),
+        };
+
+        // Create the settings string with the clients in a "clients" array
+        let settings_obj_str = serde_json::json!({
+            "clients": clients
+        })
+        .to_string();
+
+        let request_body = serde_json::json!({
+            "id": inbound_id,
+            "settings": settings_obj_str
+        });
+
+        let response = self
+            .api_post_request(add_client_endpoint, Some(&request_body))
+            .await?;
+        ensure_success(&response)
+    }
+}
@@ -0,0 +1,357 @@
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::api::XUiClient;
+use crate::errors::MyError;
+use crate::fleet::{Fleet, FleetResults};
+use crate::models::{Inbound, ServerStatus};
+use crate::online::parse_online_clients;
+
+/// Time allowed to a scraper to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pause after a failed `accept`, e.g. when out of file descriptors.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Data of one panel exposed as Prometheus metrics.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PanelMetrics {
+    pub inbounds: Vec<Inbound>,
+    /// Emails of the clients online.
+    pub online: Vec<String>,
+    /// Status of the server, `None` if the panel does not provide it.
+    pub status: Option<ServerStatus>,
+}
+
+impl PanelMetrics {
+    /// Fetches the inbounds, online clients and server status of a panel.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `MyError` if the inbounds or online clients could not be fetched; a
+    /// failing server status is left out.
+    pub async fn collect(client: &mut XUiClient) -> Result<Self, MyError> {
+        let inbounds = client.get_inbounds_typed().await?;
+        let online = parse_online_clients(client.get_online_clients().await?)?;
+        let status = client.get_server_status_typed().await.ok();
+
+        Ok(Self {
+            inbounds,
+            online,
+            status,
+        })
+    }
+}
+
+/// Collects the metrics of every panel of the fleet.
+pub async fn collect(fleet: &mut Fleet) -> FleetResults<PanelMetrics> {
+    fleet
+        .run(|client| Box::pin(async move { PanelMetrics::collect(client).await }))
+        .await
+}
+
+/// A metric family in the Prometheus text format.
+struct Family {
+    name: &'static str,
+    help: &'static str,
+    samples: Vec<(String, f64)>,
+}
+
+impl Family {
+    fn new(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            samples: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, labels: &[(&str, &str)], value: impl Into<f64>) {
+        let labels = labels
+            .iter()
+            .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        self.samples.push((labels, value.into()));
+    }
+
+    fn render(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} gauge", self.name);
+        for (labels, value) in &self.samples {
+            let _ = writeln!(out, "{}{{{}}} {}", self.name, labels, value);
+        }
+    }
+}
+
+/// Escapes a label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Renders collected panel data in the Prometheus text exposition format.
+///
+/// Every sample carries a `panel` label with the name of its panel; `xui_up` is `0` for
+/// panels that could not be scraped, which have no other metrics. Inbound metrics are
+/// labelled with `inbound_id`, `remark`, `protocol` and `port`, client metrics with
+/// `inbound_id` and `email`. Byte counters are exposed as gauges since the panel can
+/// reset them.
+///
+/// # Example
+///
+/// ```rust
+/// use xui_rs::exporter::{collect, render};
+/// use xui_rs::fleet::Fleet;
+///
+/// async fn example(fleet: &mut Fleet) {
+///     let metrics = collect(fleet).await;
+///     print!("{}", render(&metrics));
+/// }
+/// ```
+pub fn render(panels: &FleetResults<PanelMetrics>) -> String {
+    let mut up = Family::new("xui_up", "Whether the panel could be scraped.");
+    let mut inbound_up = Family::new(
+        "xui_inbound_up_bytes",
+        "Bytes uploaded through the inbound.",
+    );
+    let mut inbound_down = Family::new(
+        "xui_inbound_down_bytes",
+        "Bytes downloaded through the inbound.",
+    );
+    let mut inbound_quota = Family::new(
+        "xui_inbound_total_bytes",
+        "Traffic quota of the inbound in bytes, 0 for unlimited.",
+    );
+    let mut inbound_enabled = Family::new("xui_inbound_enabled", "Whether the inbound is enabled.");
+    let mut inbound_expiry = Family::new(
+        "xui_inbound_expiry_timestamp_seconds",
+        "Expiry of the inbound as a Unix timestamp, 0 for never.",
+    );
+    let mut client_up = Family::new("xui_client_up_bytes", "Bytes uploaded by the client.");
+    let mut client_down = Family::new("xui_client_down_bytes", "Bytes downloaded by the client.");
+    let mut client_quota = Family::new(
+        "xui_client_total_bytes",
+        "Traffic quota of the client in bytes, 0 for unlimited.",
+    );
+    let mut client_enabled = Family::new("xui_client_enabled", "Whether the client is enabled.");
+    let mut client_expiry = Family::new(
+        "xui_client_expiry_timestamp_seconds",
+        "Expiry of the client as a Unix timestamp, 0 for never or not started.",
+    );
+    let mut client_online = Family::new("xui_client_online", "Whether the client is online.");
+    let mut online = Family::new("xui_online_clients", "Number of clients online.");
+    let mut cpu = Family::new(
+        "xui_server_cpu_percent",
+        "CPU usage of the server in percent.",
+    );
+    let mut mem_used = Family::new("xui_server_memory_used_bytes", "Memory used on the server.");
+    let mut mem_total = Family::new("xui_server_memory_total_bytes", "Memory of the server.");
+    let mut disk_used = Family::new(
+        "xui_server_disk_used_bytes",
+        "Disk space used on the server.",
+    );
+    let mut disk_total = Family::new("xui_server_disk_total_bytes", "Disk space of the server.");
+    let mut uptime = Family::new("xui_server_uptime_seconds", "Uptime of the server.");
+    let mut xray_running = Family::new("xui_xray_running", "Whether Xray is running.");
+
+    for (panel, result) in panels {
+        let panel = panel.as_str();
+        let metrics = match result {
+            Ok(metrics) => metrics,
+            Err(_) => {
+                up.add(&[("panel", panel)], 0);
+                continue;
+            }
+        };
+        up.add(&[("panel", panel)], 1);
+
+        let online_emails: HashSet<&str> = metrics.online.iter().map(String::as_str).collect();
+        online.add(&[("panel", panel)], online_emails.len() as f64);
+
+        for inbound in &metrics.inbounds {
+            let id = inbound.id.to_string();
+            let port = inbound.port.to_string();
+            let labels = [
+                ("panel", panel),
+                ("inbound_id", id.as_str()),
+                ("remark", inbound.remark.as_str()),
+                ("protocol", inbound.protocol.as_str()),
+                ("port", port.as_str()),
+            ];
+            inbound_up.add(&labels, inbound.up as f64);
+            inbound_down.add(&labels, inbound.down as f64);
+            inbound_quota.add(&labels, inbound.total as f64);
+            inbound_enabled.add(&labels, u8::from(inbound.enable));
+            inbound_expiry.add(&labels, expiry_seconds(inbound.expiry_time));
+
+            for stats in &inbound.client_stats {
+                let labels = [
+                    ("panel", panel),
+                    ("inbound_id", id.as_str()),
+                    ("email", stats.email.as_str()),
+                ];
+                client_up.add(&labels, stats.up as f64);
+                client_down.add(&labels, stats.down as f64);
+                client_quota.add(&labels, stats.total as f64);
+                client_enabled.add(&labels, u8::from(stats.enable));
+                client_expiry.add(&labels, expiry_seconds(stats.expiry_time));
+                client_online.add(
+                    &labels,
+                    u8::from(online_emails.contains(stats.email.as_str())),
+                );
+            }
+        }
+
+        if let Some(status) = &metrics.status {
+            let labels = [("panel", panel)];
+            cpu.add(&labels, status.cpu);
+            mem_used.add(&labels, status.mem.current as f64);
+            mem_total.add(&labels, status.mem.total as f64);
+            disk_used.add(&labels, status.disk.current as f64);
+            disk_total.add(&labels, status.disk.total as f64);
+            uptime.add(&labels, status.uptime as f64);
+            xray_running.add(&labels, u8::from(status.xray.is_running()));
+        }
+    }
+
+    let mut out = String::new();
+    for family in [
+        up,
+        inbound_up,
+        inbound_down,
+        inbound_quota,
+        inbound_enabled,
+        inbound_expiry,
+        client_up,
+        client_down,
+        client_quota,
+        client_enabled,
+        client_expiry,
+        client_online,
+        online,
+        cpu,
+        mem_used,
+        mem_total,
+        disk_used,
+        disk_total,
+        uptime,
+        xray_running,
+    ] {
+        if !family.samples.is_empty() {
+            family.render(&mut out);
+        }
+    }
+    out
+}
+
+/// Converts a panel expiry in milliseconds to seconds, "start on first use" durations
+/// (negative values) counting as no expiry.
+fn expiry_seconds(expiry_time: i64) -> f64 {
+    (expiry_time.max(0) / 1000) as f64
+}
+
+/// Serves `/metrics` on `listener`, scraping every panel of the fleet on each request.
+///
+/// A minimal HTTP/1.1 server answering `GET /metrics`. Requests are handled one at a
+/// time, so concurrent scrapes do not multiply the load on the panels: give the fleet a
+/// [`Fleet::timeout`] below the scrape timeout so that an unresponsive panel is reported
+/// as down instead of stalling every scrape. Failed connections are logged and skipped.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use tokio::net::TcpListener;
+/// use xui_rs::exporter::serve;
+/// use xui_rs::fleet::Fleet;
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let fleet = Fleet::load("panels.yaml")?.timeout(Duration::from_secs(8));
+///     let listener = TcpListener::bind("0.0.0.0:9617").await?;
+///     serve(fleet, listener).await
+/// }
+/// ```
+pub async fn serve(mut fleet: Fleet, listener: TcpListener) -> Result<(), MyError> {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                tracing::warn!(error = %err, "accepting a metrics connection failed");
+                tokio::time::sleep(ACCEPT_BACKOFF).await;
+                continue;
+            }
+        };
+        if let Err(err) = handle(stream, &mut fleet).await {
+            tracing::debug!(error = %err, "metrics request failed");
+        }
+    }
+}
+
+async fn handle(mut stream: TcpStream, fleet: &mut Fleet) -> Result<(), MyError> {
+    let request = tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream))
+        .await
+        .map_err(|_| MyError::CustomError("Timed out reading the request".to_string()))??;
+    let mut parts = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts
+        .next()
+        .and_then(|path| path.split('?').next())
+        .unwrap_or_default();
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => {
+            let metrics = collect(fleet).await;
+            (
+                "200 OK",
+                "text/plain; version=0.0.4; charset=utf-8",
+                render(&metrics),
+            )
+        }
+        ("GET", "/") => (
+            "200 OK",
+            "text/html; charset=utf-8",
+            "<html><body><a href=\"/metrics\">Metrics</a></body></html>\n".to_string(),
+        ),
+        _ => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "Not Found\n".to_string(),
+        ),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// Reads the request head; only the request line is used.
+async fn read_request(stream: &mut TcpStream) -> Result<String, MyError> {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") && request.len() < 16 * 1024 {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+    Ok(String::from_utf8_lossy(&request).into_owned())
+}
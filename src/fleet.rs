@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::api::XUiClient;
+use crate::clients::FoundClient;
+use crate::errors::MyError;
+use crate::models::{Inbound, ServerStatus};
+
+/// Default number of panels queried at the same time by a [`Fleet`].
+pub const DEFAULT_FLEET_CONCURRENCY: usize = 16;
+
+/// Results of a fan-out over a [`Fleet`], keyed by panel name.
+pub type FleetResults<T> = BTreeMap<String, Result<T, MyError>>;
+
+/// Connection settings of one panel of a [`Fleet`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PanelConfig {
+    pub name: String,
+    /// Base URL of the panel, ending with `/`.
+    pub url: String,
+    /// Login credentials. They may be left out of a file read by the `xui` command-line
+    /// tool, which takes them from the command line or the environment, but a [`Fleet`]
+    /// requires both.
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+}
+
+/// List of panels, typically loaded from a YAML or JSON file:
+///
+/// ```yaml
+/// panels:
+///   - name: de-1
+///     url: https://de-1.example.com:2053/secret/
+///     username: admin
+///     password: hunter2
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FleetConfig {
+    #[serde(default)]
+    pub panels: Vec<PanelConfig>,
+}
+
+impl FleetConfig {
+    /// Parses a fleet configuration from YAML.
+    pub fn from_yaml(yaml: &str) -> Result<Self, MyError> {
+        serde_yaml::from_str(yaml)
+            .map_err(|e| MyError::CustomError(format!("Invalid fleet configuration YAML: {}", e)))
+    }
+
+    /// Parses a fleet configuration from JSON.
+    pub fn from_json(json: &str) -> Result<Self, MyError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Loads a fleet configuration from a file, parsed as JSON if its extension is `.json`
+    /// and as YAML otherwise.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MyError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&content),
+            _ => Self::from_yaml(&content),
+        }
+    }
+}
+
+/// A set of named panel clients queried together.
+///
+/// Fan-out methods run against all panels concurrently (up to `concurrency` at a time).
+/// A failing or unreachable panel does not affect the others: every method returns one
+/// `Result` per panel, keyed by panel name.
+///
+/// Panels log in lazily on their first request, so building a fleet does not contact
+/// any panel.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use xui_rs::fleet::Fleet;
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let mut fleet = Fleet::load("fleet.yaml")?.timeout(Duration::from_secs(10));
+///
+///     for (panel, result) in fleet.find_client_everywhere("user@example.com").await {
+///         match result {
+///             Ok(Some(found)) => println!("{}: inbound {}", panel, found.inbound.id),
+///             Ok(None) => {}
+///             Err(err) => eprintln!("{}: {}", panel, err),
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct Fleet {
+    panels: BTreeMap<String, XUiClient>,
+    concurrency: Option<usize>,
+    timeout: Option<Duration>,
+}
+
+impl Fleet {
+    /// Creates an empty fleet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a fleet from a configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `MyError` if a panel URL is invalid, a panel has no username or password,
+    /// or a panel name is used twice.
+    pub fn from_config(config: &FleetConfig) -> Result<Self, MyError> {
+        let mut fleet = Self::new();
+        for panel in &config.panels {
+            if fleet.panels.contains_key(&panel.name) {
+                return Err(MyError::CustomError(format!(
+                    "Panel '{}' is declared more than once",
+                    panel.name
+                )));
+            }
+            if panel.username.is_empty() || panel.password.is_empty() {
+                return Err(MyError::CustomError(format!(
+                    "Panel '{}' has no username or password",
+                    panel.name
+                )));
+            }
+            let client = XUiClient::new(panel.url.as_str())?
+                .with_credentials(&panel.username, &panel.password);
+            fleet.add(&panel.name, client);
+        }
+        Ok(fleet)
+    }
+
+    /// Loads the fleet configuration from a file, see [`FleetConfig::load`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MyError> {
+        Self::from_config(&FleetConfig::load(path)?)
+    }
+
+    /// Adds a panel, replacing any panel with the same name.
+    pub fn add(&mut self, name: impl Into<String>, client: XUiClient) -> &mut Self {
+        self.panels.insert(name.into(), client);
+        self
+    }
+
+    /// Sets how many panels are queried at the same time (at least 1).
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency.max(1));
+        self
+    }
+
+    /// Sets a time limit for each panel's part of a fan-out.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the names of the panels, in alphabetical order.
+    pub fn names(&self) -> Vec<&str> {
+        self.panels.keys().map(String::as_str).collect()
+    }
+
+    /// Returns the client of a panel.
+    pub fn panel(&mut self, name: &str) -> Option<&mut XUiClient> {
+        self.panels.get_mut(name)
+    }
+
+    /// Runs `op` against every panel and collects the results by panel name.
+    ///
+    /// This is the building block of the other fan-out methods and can be used for any
+    /// custom per-panel operation:
+    ///
+    /// ```rust
+    /// use xui_rs::fleet::Fleet;
+    ///
+    /// async fn example(fleet: &mut Fleet) {
+    ///     let backups = fleet
+    ///         .run(|client| Box::pin(async move { client.get_backup().await }))
+    ///         .await;
+    ///     println!("{:?}", backups);
+    /// }
+    /// ```
+    pub async fn run<T, F>(&mut self, op: F) -> FleetResults<T>
+    where
+        T: Send,
+        F: for<'a> Fn(&'a mut XUiClient) -> BoxFuture<'a, Result<T, MyError>>,
+    {
+        let concurrency = self.concurrency.unwrap_or(DEFAULT_FLEET_CONCURRENCY);
+        let timeout = self.timeout;
+        let op = &op;
+
+        stream::iter(self.panels.iter_mut())
+            .map(|(name, client)| async move {
+                let result = match timeout {
+                    Some(limit) => match tokio::time::timeout(limit, op(client)).await {
+                        Ok(result) => result,
+                        Err(_) => Err(MyError::CustomError(format!(
+                            "Panel '{}' did not answer within {:?}",
+                            name, limit
+                        ))),
+                    },
+                    None => op(client).await,
+                };
+                (name.clone(), result)
+            })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await
+    }
+
+    /// Fetches the inbounds of every panel.
+    pub async fn get_inbounds_all(&mut self) -> FleetResults<Vec<Inbound>> {
+        self.run(|client| Box::pin(async move { client.get_inbounds_typed().await }))
+            .await
+    }
+
+    /// Looks up a client by email on every panel.
+    pub async fn find_client_everywhere(
+        &mut self,
+        email: impl Into<String>,
+    ) -> FleetResults<Option<FoundClient>> {
+        let email = email.into();
+        self.run(|client| {
+            let email = email.clone();
+            Box::pin(async move { client.find_client(email).await })
+        })
+        .await
+    }
+
+    /// Fetches the server status of every panel.
+    pub async fn server_status_all(&mut self) -> FleetResults<ServerStatus> {
+        self.run(|client| Box::pin(async move { client.get_server_status_typed().await }))
+            .await
+    }
+}
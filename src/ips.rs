@@ -0,0 +1,382 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api::XUiClient;
+use crate::bulk::BulkOutcome;
+use crate::errors::MyError;
+use crate::models::{ApiResponse, ensure_success};
+use crate::ops::{MILLIS_PER_DAY, now_millis};
+
+/// An IP address a client connected from, as recorded by the panel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientIp {
+    pub ip: String,
+    /// When the IP was last seen, as a Unix timestamp in milliseconds, if the panel
+    /// records it.
+    pub last_seen: Option<i64>,
+}
+
+/// Parses the response of `get_client_ips`.
+///
+/// Panels answer with a JSON array of IPs (as a list or a JSON-encoded string), with
+/// entries such as `"1.2.3.4"`, `"1.2.3.4 (2024-05-01 10:00:00)"` or
+/// `{"ip": "1.2.3.4", "timestamp": 1714557600000}`, or with the message `No IP Record`.
+/// Timestamps in seconds or as UTC date-times are converted to milliseconds.
+pub fn parse_client_ips(response: Value) -> Result<Vec<ClientIp>, MyError> {
+    let entries = match ApiResponse::<Value>::from_value(response)?.into_optional()? {
+        Some(Value::Array(entries)) => entries,
+        Some(Value::String(text)) => match serde_json::from_str(&text) {
+            Ok(Value::Array(entries)) => entries,
+            _ => text
+                .split([',', '\n'])
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty() && !entry.eq_ignore_ascii_case("No IP Record"))
+                .map(|entry| Value::String(entry.to_string()))
+                .collect(),
+        },
+        _ => Vec::new(),
+    };
+
+    Ok(entries.iter().filter_map(parse_entry).collect())
+}
+
+fn parse_entry(entry: &Value) -> Option<ClientIp> {
+    let (ip, last_seen) = match entry {
+        Value::String(entry) => match entry.split_once(" (") {
+            Some((ip, time)) => (
+                ip,
+                parse_timestamp(&Value::from(time.trim_end_matches(')'))),
+            ),
+            None => (entry.as_str(), None),
+        },
+        Value::Object(entry) => (
+            entry.get("ip")?.as_str()?,
+            ["timestamp", "lastSeen", "time"]
+                .iter()
+                .find_map(|key| entry.get(*key).and_then(parse_timestamp)),
+        ),
+        _ => return None,
+    };
+
+    let ip = ip.trim();
+    (!ip.is_empty()).then(|| ClientIp {
+        ip: ip.to_string(),
+        last_seen,
+    })
+}
+
+/// Parses a timestamp in seconds or milliseconds, or a `YYYY-MM-DD HH:MM:SS` UTC date-time,
+/// into milliseconds. Returns `None` for values out of range.
+fn parse_timestamp(value: &Value) -> Option<i64> {
+    let number = match value {
+        Value::Number(number) => number.as_i64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    };
+    if let Some(number) = number {
+        // Seconds until year 5138, milliseconds afterwards
+        return if number < 100_000_000_000 {
+            number.checked_mul(1000)
+        } else {
+            Some(number)
+        };
+    }
+
+    let text = value.as_str()?.trim();
+    let (date, time) = text.split_once([' ', 'T']).unwrap_or((text, "00:00:00"));
+    let date: Vec<i64> = date
+        .split('-')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    let time: Vec<i64> = time
+        .trim_end_matches('Z')
+        .split(':')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    let (&[year, month, day], &[hour, minute, second]) = (date.as_slice(), time.as_slice()) else {
+        return None;
+    };
+
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || !(0..24).contains(&hour)
+        || !(0..60).contains(&minute)
+        || !(0..=60).contains(&second)
+    {
+        return None;
+    }
+
+    let seconds = hour * 3600 + minute * 60 + second;
+    days_from_civil(year, month, day)?
+        .checked_mul(MILLIS_PER_DAY)?
+        .checked_add(seconds * 1000)
+}
+
+/// Number of days between 1970-01-01 and the given date of the proleptic Gregorian calendar,
+/// `None` if it does not fit in an `i64`.
+fn days_from_civil(year: i64, month: i64, day: i64) -> Option<i64> {
+    let year = if month <= 2 {
+        year.checked_sub(1)?
+    } else {
+        year
+    };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era.checked_mul(146_097)?.checked_add(day_of_era - 719_468)
+}
+
+/// Action taken on clients flagged by [`IpAnalyzer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpAction {
+    /// Disables the client.
+    Disable,
+    /// Clears the IP records of the client, restarting the count.
+    ClearIps,
+}
+
+/// Result of an [`IpAction`] on an offender.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionResult {
+    pub action: IpAction,
+    pub outcome: BulkOutcome,
+}
+
+/// A client connected from more distinct IPs than allowed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Offender {
+    pub inbound_id: u64,
+    pub email: String,
+    /// Number of distinct IPs the client was allowed.
+    pub limit: usize,
+    /// The distinct IPs counted against the limit, most recently seen first.
+    pub ips: Vec<ClientIp>,
+    pub actions: Vec<ActionResult>,
+}
+
+/// Report of [`IpAnalyzer::run`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IpReport {
+    /// Number of clients whose IPs were checked.
+    pub checked: usize,
+    pub offenders: Vec<Offender>,
+    /// Clients whose IPs could not be fetched, with the error.
+    pub failed: BTreeMap<String, String>,
+}
+
+/// Flags clients connecting from more distinct IPs than allowed within a time window,
+/// enforcing `limitIp`-like rules without relying on the panel's fail2ban integration.
+///
+/// IPs without a timestamp are always counted. Actions are only taken on offenders and
+/// can be previewed with [`IpAnalyzer::dry_run`].
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use xui_rs::api::XUiClient;
+/// use xui_rs::ips::{IpAction, IpAnalyzer};
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+///     client.login("admin", "password").await?;
+///
+///     let report = IpAnalyzer::new(3)
+///         .window(Duration::from_secs(3600))
+///         .use_client_limit(true)
+///         .action(IpAction::ClearIps)
+///         .run(&mut client)
+///         .await?;
+///     for offender in &report.offenders {
+///         println!("{}: {} IPs (limit {})", offender.email, offender.ips.len(), offender.limit);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct IpAnalyzer {
+    max_ips: usize,
+    window: Option<Duration>,
+    use_client_limit: bool,
+    actions: Vec<IpAction>,
+    dry_run: bool,
+}
+
+impl IpAnalyzer {
+    /// Creates an analyzer flagging clients with more than `max_ips` distinct IPs, over
+    /// all recorded IPs and without actions.
+    pub fn new(max_ips: usize) -> Self {
+        Self {
+            max_ips,
+            window: None,
+            use_client_limit: false,
+            actions: Vec::new(),
+            dry_run: false,
+        }
+    }
+
+    /// Only counts IPs seen within `window` before the analysis.
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    /// When enabled, clients with a `limitIp` use it instead of `max_ips`.
+    pub fn use_client_limit(mut self, use_client_limit: bool) -> Self {
+        self.use_client_limit = use_client_limit;
+        self
+    }
+
+    /// Adds an action taken on every offender, in order.
+    pub fn action(mut self, action: IpAction) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    /// When enabled, actions are reported as planned but not performed.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Returns the limit applying to a client with the given `limitIp`.
+    pub fn limit_for(&self, limit_ip: u32) -> usize {
+        if self.use_client_limit && limit_ip > 0 {
+            limit_ip as usize
+        } else {
+            self.max_ips
+        }
+    }
+
+    /// Returns the distinct IPs counted at `now` (Unix milliseconds), most recently seen
+    /// first, if there are more than `limit`.
+    pub fn offending_ips(&self, ips: &[ClientIp], limit: usize, now: i64) -> Option<Vec<ClientIp>> {
+        let since = self
+            .window
+            .map(|window| now.saturating_sub(i64::try_from(window.as_millis()).unwrap_or(i64::MAX)))
+            .unwrap_or(i64::MIN);
+
+        let mut distinct: HashMap<&str, Option<i64>> = HashMap::new();
+        for ip in ips {
+            if ip.last_seen.is_some_and(|last_seen| last_seen < since) {
+                continue;
+            }
+            let last_seen = distinct.entry(ip.ip.as_str()).or_insert(ip.last_seen);
+            *last_seen = (*last_seen).max(ip.last_seen);
+        }
+        if distinct.len() <= limit {
+            return None;
+        }
+
+        let mut ips: Vec<ClientIp> = distinct
+            .into_iter()
+            .map(|(ip, last_seen)| ClientIp {
+                ip: ip.to_string(),
+                last_seen,
+            })
+            .collect();
+        ips.sort_by(|a, b| b.last_seen.cmp(&a.last_seen).then(a.ip.cmp(&b.ip)));
+        Some(ips)
+    }
+
+    /// Checks the IPs of every enabled client of the panel and acts on offenders.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `MyError` if the inbounds could not be fetched; failures on single clients
+    /// are reported in the [`IpReport`].
+    pub async fn run(&self, client: &mut XUiClient) -> Result<IpReport, MyError> {
+        let mut report = IpReport::default();
+
+        for inbound in client.get_inbounds_typed().await? {
+            for entry in inbound.clients()? {
+                if !entry.enable {
+                    continue;
+                }
+                let ips = match client.get_client_ips_typed(&entry.email).await {
+                    Ok(ips) => ips,
+                    Err(err) => {
+                        report.failed.insert(entry.email, err.to_string());
+                        continue;
+                    }
+                };
+                report.checked += 1;
+
+                let limit = self.limit_for(entry.limit_ip);
+                let Some(ips) = self.offending_ips(&ips, limit, now_millis()) else {
+                    continue;
+                };
+
+                let mut actions = Vec::new();
+                for action in &self.actions {
+                    let outcome = if self.dry_run {
+                        BulkOutcome::Planned
+                    } else {
+                        match self.apply(client, *action, inbound.id, &entry.email).await {
+                            Ok(()) => BulkOutcome::Done,
+                            Err(err) => BulkOutcome::Failed(err.to_string()),
+                        }
+                    };
+                    actions.push(ActionResult {
+                        action: *action,
+                        outcome,
+                    });
+                }
+
+                report.offenders.push(Offender {
+                    inbound_id: inbound.id,
+                    email: entry.email,
+                    limit,
+                    ips,
+                    actions,
+                });
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn apply(
+        &self,
+        client: &mut XUiClient,
+        action: IpAction,
+        inbound_id: u64,
+        email: &str,
+    ) -> Result<(), MyError> {
+        match action {
+            IpAction::Disable => client.ops().suspend(inbound_id, email).await.map(|_| ()),
+            IpAction::ClearIps => ensure_success(&client.clear_client_ips(email).await?),
+        }
+    }
+}
+
+impl XUiClient {
+    /// Retrieves the IP records of a client as typed entries, see [`parse_client_ips`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///
+    ///     for ip in client.get_client_ips_typed("user@example.com").await? {
+    ///         println!("{} (last seen {:?})", ip.ip, ip.last_seen);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_client_ips_typed(
+        &mut self,
+        client_email: impl Into<String>,
+    ) -> Result<Vec<ClientIp>, MyError> {
+        parse_client_ips(self.get_client_ips(client_email).await?)
+    }
+}
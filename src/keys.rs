@@ -0,0 +1,90 @@
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::errors::MyError;
+
+/// A base64-encoded X25519 key pair, as used by REALITY and WireGuard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPair {
+    pub private_key: String,
+    pub public_key: String,
+}
+
+/// Generates a REALITY key pair, encoded like `xray x25519` (URL-safe base64 without padding).
+pub fn reality_keypair() -> KeyPair {
+    keypair(random_bytes(), URL_SAFE_NO_PAD)
+}
+
+/// Derives the REALITY public key (`pbk` of share links) from a private key.
+pub fn reality_public_key(private_key: &str) -> Result<String, MyError> {
+    public_key(private_key, URL_SAFE_NO_PAD)
+}
+
+/// Generates a WireGuard key pair, encoded like `wg genkey` (standard base64).
+pub fn wireguard_keypair() -> KeyPair {
+    keypair(random_bytes(), STANDARD)
+}
+
+/// Derives the WireGuard public key from a private key.
+pub fn wireguard_public_key(private_key: &str) -> Result<String, MyError> {
+    public_key(private_key, STANDARD)
+}
+
+/// Generates a random REALITY short ID of `len` hexadecimal characters (at most 16).
+pub fn short_id(len: usize) -> String {
+    let bytes: [u8; 8] = random_bytes();
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    hex[..len.min(16)].to_string()
+}
+
+/// Generates a random key for a Shadowsocks 2022 method: 16 bytes for
+/// `2022-blake3-aes-128-gcm`, 32 bytes otherwise, base64-encoded.
+pub fn shadowsocks_2022_key(method: &str) -> String {
+    let key: [u8; 32] = random_bytes();
+    let len = if method.contains("aes-128") { 16 } else { 32 };
+    STANDARD.encode(&key[..len])
+}
+
+/// Generates a random alphanumeric password, e.g. for Trojan clients.
+pub fn random_password() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Returns random bytes from the operating system.
+///
+/// # Panics
+///
+/// Panics if the system random number generator is unavailable.
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    getrandom::fill(&mut bytes).expect("system random number generator unavailable");
+    bytes
+}
+
+fn keypair(secret: [u8; 32], engine: impl Engine) -> KeyPair {
+    let secret = StaticSecret::from(secret);
+    let public = PublicKey::from(&secret);
+
+    KeyPair {
+        private_key: engine.encode(secret.to_bytes()),
+        public_key: engine.encode(public.as_bytes()),
+    }
+}
+
+fn public_key(private_key: &str, engine: impl Engine) -> Result<String, MyError> {
+    // Accept keys in either alphabet, with or without padding
+    let normalized = private_key
+        .trim()
+        .trim_end_matches('=')
+        .replace('+', "-")
+        .replace('/', "_");
+    let bytes = URL_SAFE_NO_PAD
+        .decode(normalized)
+        .map_err(|e| MyError::CustomError(format!("Invalid X25519 private key: {}", e)))?;
+    let secret: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| MyError::CustomError("X25519 private key must be 32 bytes".to_string()))?;
+
+    Ok(keypair(secret, engine).public_key)
+}
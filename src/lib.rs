@@ -1,2 +1,28 @@
+pub mod alerts;
 pub mod api;
+pub mod builder;
+pub mod bulk;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod clients;
 pub mod errors;
+#[cfg(feature = "exporter")]
+pub mod exporter;
+pub mod fleet;
+pub mod ips;
+pub mod keys;
+pub mod links;
+pub mod metrics;
+pub mod migrate;
+pub mod models;
+pub mod online;
+pub mod ops;
+pub mod poller;
+#[cfg(feature = "qr")]
+pub mod qr;
+pub mod reconcile;
+pub mod snapshot;
+pub mod store;
+pub mod subscription;
+pub mod validate;
+pub mod xray;
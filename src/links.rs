@@ -0,0 +1,592 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode};
+use serde_json::{Value, json};
+
+use crate::errors::MyError;
+use crate::models::{Client, Inbound, Protocol};
+
+/// Transport and security parameters of an inbound, as seen by a connecting client.
+///
+/// Extracted from the inbound's `streamSettings`; empty strings mean "not set".
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamParams {
+    /// Transport: `tcp`, `ws`, `grpc`, `httpupgrade`, `xhttp`, `kcp`, ...
+    pub network: String,
+    /// Security layer: `none`, `tls` or `reality`.
+    pub security: String,
+    pub path: String,
+    pub host: String,
+    pub service_name: String,
+    pub header_type: String,
+    /// gRPC (`gun`/`multi`) or XHTTP mode.
+    pub mode: String,
+    pub seed: String,
+    pub sni: String,
+    pub alpn: Vec<String>,
+    pub fingerprint: String,
+    pub public_key: String,
+    pub short_id: String,
+    pub spider_x: String,
+}
+
+impl StreamParams {
+    /// Extracts the client-facing parameters from an inbound's `streamSettings`.
+    pub fn from_stream_settings(stream: &Value) -> Self {
+        let str_at = |value: &Value, pointer: &str| -> String {
+            value
+                .pointer(pointer)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+        let first_str_at = |value: &Value, pointer: &str| -> String {
+            match value.pointer(pointer) {
+                Some(Value::Array(items)) => items
+                    .first()
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                Some(Value::String(s)) => s.clone(),
+                _ => String::new(),
+            }
+        };
+
+        let network = match str_at(stream, "/network") {
+            network if network.is_empty() => "tcp".to_string(),
+            network => network,
+        };
+        let security = match str_at(stream, "/security") {
+            security if security.is_empty() => "none".to_string(),
+            security => security,
+        };
+
+        let mut params = StreamParams {
+            network,
+            security,
+            ..Default::default()
+        };
+
+        match params.network.as_str() {
+            "tcp" => {
+                params.header_type = str_at(stream, "/tcpSettings/header/type");
+                if params.header_type == "http" {
+                    params.path = first_str_at(stream, "/tcpSettings/header/request/path");
+                    params.host = first_str_at(stream, "/tcpSettings/header/request/headers/Host");
+                }
+            }
+            "ws" => {
+                params.path = str_at(stream, "/wsSettings/path");
+                params.host = str_at(stream, "/wsSettings/host");
+                if params.host.is_empty() {
+                    params.host = first_str_at(stream, "/wsSettings/headers/Host");
+                }
+            }
+            "grpc" => {
+                params.service_name = str_at(stream, "/grpcSettings/serviceName");
+                let multi_mode = stream
+                    .pointer("/grpcSettings/multiMode")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                params.mode = if multi_mode { "multi" } else { "gun" }.to_string();
+            }
+            "httpupgrade" => {
+                params.path = str_at(stream, "/httpupgradeSettings/path");
+                params.host = str_at(stream, "/httpupgradeSettings/host");
+            }
+            "xhttp" | "splithttp" => {
+                params.path = str_at(stream, "/xhttpSettings/path");
+                params.host = str_at(stream, "/xhttpSettings/host");
+                params.mode = str_at(stream, "/xhttpSettings/mode");
+            }
+            "kcp" => {
+                params.header_type = str_at(stream, "/kcpSettings/header/type");
+                params.seed = str_at(stream, "/kcpSettings/seed");
+            }
+            _ => {}
+        }
+
+        match params.security.as_str() {
+            "tls" => {
+                params.sni = str_at(stream, "/tlsSettings/serverName");
+                params.fingerprint = str_at(stream, "/tlsSettings/settings/fingerprint");
+                params.alpn = stream
+                    .pointer("/tlsSettings/alpn")
+                    .and_then(Value::as_array)
+                    .map(|alpn| {
+                        alpn.iter()
+                            .filter_map(Value::as_str)
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+            }
+            "reality" => {
+                params.sni = first_str_at(stream, "/realitySettings/serverNames");
+                params.short_id = first_str_at(stream, "/realitySettings/shortIds");
+                params.public_key = str_at(stream, "/realitySettings/settings/publicKey");
+                params.fingerprint = str_at(stream, "/realitySettings/settings/fingerprint");
+                params.spider_x = str_at(stream, "/realitySettings/settings/spiderX");
+            }
+            _ => {}
+        }
+
+        if params.header_type == "none" {
+            params.header_type.clear();
+        }
+
+        params
+    }
+
+    /// Appends the transport/security query parameters used by `vless://` and `trojan://` links.
+    fn push_query(&self, query: &mut Vec<(&'static str, String)>) {
+        query.push(("type", self.network.clone()));
+        query.push(("security", self.security.clone()));
+
+        match self.network.as_str() {
+            "tcp" | "kcp" if !self.header_type.is_empty() => {
+                query.push(("headerType", self.header_type.clone()));
+            }
+            "grpc" => {
+                query.push(("serviceName", self.service_name.clone()));
+                query.push(("mode", self.mode.clone()));
+            }
+            "xhttp" | "splithttp" if !self.mode.is_empty() => {
+                query.push(("mode", self.mode.clone()));
+            }
+            _ => {}
+        }
+        if !self.path.is_empty() {
+            query.push(("path", self.path.clone()));
+        }
+        if !self.host.is_empty() {
+            query.push(("host", self.host.clone()));
+        }
+        if !self.seed.is_empty() {
+            query.push(("seed", self.seed.clone()));
+        }
+
+        if self.security == "tls" || self.security == "reality" {
+            if !self.sni.is_empty() {
+                query.push(("sni", self.sni.clone()));
+            }
+            if !self.fingerprint.is_empty() {
+                query.push(("fp", self.fingerprint.clone()));
+            }
+            if !self.alpn.is_empty() {
+                query.push(("alpn", self.alpn.join(",")));
+            }
+        }
+        if self.security == "reality" {
+            query.push(("pbk", self.public_key.clone()));
+            if !self.short_id.is_empty() {
+                query.push(("sid", self.short_id.clone()));
+            }
+            if !self.spider_x.is_empty() {
+                query.push(("spx", self.spider_x.clone()));
+            }
+        }
+    }
+}
+
+/// A client-side view of an inbound/client pair: everything needed to connect to it.
+///
+/// This is the common representation behind share links (`vless://`, `vmess://`,
+/// `trojan://`, `ss://`) and the subscription formats built from them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShareLink {
+    pub protocol: Protocol,
+    /// Host name or IP address clients connect to.
+    pub address: String,
+    pub port: u16,
+    /// UUID for VMess/VLESS, password for Trojan/Shadowsocks.
+    pub credential: String,
+    /// Shadowsocks cipher, empty for other protocols.
+    pub method: String,
+    /// VLESS flow (e.g. `xtls-rprx-vision`), empty if unused.
+    pub flow: String,
+    /// Display name of the link.
+    pub remark: String,
+    pub stream: StreamParams,
+}
+
+impl ShareLink {
+    /// Builds the share link of `client` on `inbound`, reachable at `address`.
+    ///
+    /// The remark follows the panel's own `<inbound remark>-<client email>` convention.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `MyError::CustomError` if the inbound protocol has no share-link format
+    /// or the client lacks the credential the protocol requires.
+    pub fn from_inbound(
+        inbound: &Inbound,
+        client: &Client,
+        address: &str,
+    ) -> Result<Self, MyError> {
+        let credential = match inbound.protocol {
+            Protocol::Vmess | Protocol::Vless => client.id.clone(),
+            Protocol::Trojan => client.password.clone(),
+            Protocol::Shadowsocks => {
+                let inbound_password = inbound
+                    .settings
+                    .get("password")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                // Shadowsocks 2022 multi-user inbounds expect "<server key>:<user key>".
+                match &client.password {
+                    Some(password) if !inbound_password.is_empty() => {
+                        Some(format!("{}:{}", inbound_password, password))
+                    }
+                    other => other.clone(),
+                }
+            }
+            ref other => {
+                return Err(MyError::CustomError(format!(
+                    "Protocol '{}' has no share link format",
+                    other
+                )));
+            }
+        };
+
+        let credential = match credential {
+            Some(credential) if !credential.is_empty() => credential,
+            _ => {
+                return Err(MyError::CustomError(format!(
+                    "Client '{}' has no credential for protocol '{}'",
+                    client.email, inbound.protocol
+                )));
+            }
+        };
+
+        let method = if inbound.protocol == Protocol::Shadowsocks {
+            inbound
+                .settings
+                .get("method")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .or_else(|| client.method.clone())
+                .unwrap_or_default()
+        } else {
+            String::new()
+        };
+
+        let remark = if inbound.remark.is_empty() {
+            client.email.clone()
+        } else {
+            format!("{}-{}", inbound.remark, client.email)
+        };
+
+        Ok(Self {
+            protocol: inbound.protocol.clone(),
+            address: address.to_string(),
+            port: inbound.port,
+            credential,
+            method,
+            flow: if inbound.protocol == Protocol::Vless {
+                client.flow.clone()
+            } else {
+                String::new()
+            },
+            remark,
+            stream: StreamParams::from_stream_settings(&inbound.stream_settings),
+        })
+    }
+
+    /// Encodes the link as a URI understood by common clients (v2rayN, v2rayNG, Hiddify, ...).
+    pub fn to_uri(&self) -> Result<String, MyError> {
+        let fragment = encode(&self.remark);
+        let host = if self.address.contains(':') && !self.address.starts_with('[') {
+            format!("[{}]", self.address)
+        } else {
+            self.address.clone()
+        };
+
+        match self.protocol {
+            Protocol::Vless => {
+                let mut query = vec![("encryption", "none".to_string())];
+                self.stream.push_query(&mut query);
+                if !self.flow.is_empty() {
+                    query.push(("flow", self.flow.clone()));
+                }
+                Ok(format!(
+                    "vless://{}@{}:{}?{}#{}",
+                    encode(&self.credential),
+                    host,
+                    self.port,
+                    encode_query(&query),
+                    fragment
+                ))
+            }
+            Protocol::Trojan => {
+                let mut query = Vec::new();
+                self.stream.push_query(&mut query);
+                Ok(format!(
+                    "trojan://{}@{}:{}?{}#{}",
+                    encode(&self.credential),
+                    host,
+                    self.port,
+                    encode_query(&query),
+                    fragment
+                ))
+            }
+            Protocol::Shadowsocks => {
+                let user_info = STANDARD.encode(format!("{}:{}", self.method, self.credential));
+                Ok(format!(
+                    "ss://{}@{}:{}?type={}#{}",
+                    user_info, host, self.port, self.stream.network, fragment
+                ))
+            }
+            Protocol::Vmess => {
+                let stream = &self.stream;
+                let path = if stream.network == "grpc" {
+                    &stream.service_name
+                } else {
+                    &stream.path
+                };
+                let header_type = if stream.network == "grpc" {
+                    &stream.mode
+                } else {
+                    &stream.header_type
+                };
+                let vmess = json!({
+                    "v": "2",
+                    "ps": self.remark,
+                    "add": self.address,
+                    "port": self.port,
+                    "id": self.credential,
+                    "aid": 0,
+                    "scy": "auto",
+                    "net": stream.network,
+                    "type": if header_type.is_empty() { "none" } else { header_type },
+                    "host": stream.host,
+                    "path": path,
+                    "tls": if stream.security == "tls" { "tls" } else { "" },
+                    "sni": stream.sni,
+                    "alpn": stream.alpn.join(","),
+                    "fp": stream.fingerprint,
+                });
+                Ok(format!("vmess://{}", STANDARD.encode(vmess.to_string())))
+            }
+            ref other => Err(MyError::CustomError(format!(
+                "Protocol '{}' has no share link format",
+                other
+            ))),
+        }
+    }
+}
+
+impl std::str::FromStr for ShareLink {
+    type Err = MyError;
+
+    /// Parses a `vless://`, `vmess://`, `trojan://` or `ss://` share link.
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        let uri = uri.trim();
+        let (scheme, rest) = uri
+            .split_once("://")
+            .ok_or_else(|| MyError::CustomError(format!("Not a share link: '{}'", uri)))?;
+
+        match scheme {
+            "vless" | "trojan" => parse_url_link(uri),
+            "vmess" => parse_vmess_link(rest),
+            "ss" => parse_ss_link(rest),
+            other => Err(MyError::CustomError(format!(
+                "Unsupported share link scheme '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+fn parse_url_link(uri: &str) -> Result<ShareLink, MyError> {
+    let url = url::Url::parse(uri)?;
+    let protocol = Protocol::from(url.scheme().to_string());
+    let query: std::collections::HashMap<String, String> = url.query_pairs().into_owned().collect();
+    let param = |key: &str| query.get(key).cloned().unwrap_or_default();
+
+    let stream = StreamParams {
+        network: match param("type") {
+            network if network.is_empty() => "tcp".to_string(),
+            network => network,
+        },
+        security: match param("security") {
+            security if security.is_empty() => "none".to_string(),
+            security => security,
+        },
+        path: param("path"),
+        host: param("host"),
+        service_name: param("serviceName"),
+        header_type: param("headerType"),
+        mode: param("mode"),
+        seed: param("seed"),
+        sni: param("sni"),
+        alpn: split_list(&param("alpn")),
+        fingerprint: param("fp"),
+        public_key: param("pbk"),
+        short_id: param("sid"),
+        spider_x: param("spx"),
+    };
+
+    Ok(ShareLink {
+        protocol,
+        address: url
+            .host_str()
+            .unwrap_or_default()
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string(),
+        port: url.port().unwrap_or(443),
+        credential: decode(url.username()),
+        method: String::new(),
+        flow: param("flow"),
+        remark: decode(url.fragment().unwrap_or_default()),
+        stream,
+    })
+}
+
+fn parse_vmess_link(payload: &str) -> Result<ShareLink, MyError> {
+    let decoded = decode_base64(payload)
+        .ok_or_else(|| MyError::CustomError("Invalid base64 in vmess link".to_string()))?;
+    let vmess: Value = serde_json::from_slice(&decoded)?;
+    let field = |key: &str| match vmess.get(key) {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    };
+
+    let network = match field("net") {
+        network if network.is_empty() => "tcp".to_string(),
+        network => network,
+    };
+    let (path, service_name, header_type, mode) = if network == "grpc" {
+        (String::new(), field("path"), String::new(), field("type"))
+    } else {
+        (field("path"), String::new(), field("type"), String::new())
+    };
+
+    Ok(ShareLink {
+        protocol: Protocol::Vmess,
+        address: field("add"),
+        port: field("port").parse()?,
+        credential: field("id"),
+        method: String::new(),
+        flow: String::new(),
+        remark: field("ps"),
+        stream: StreamParams {
+            network,
+            security: match field("tls") {
+                tls if tls.is_empty() => "none".to_string(),
+                tls => tls,
+            },
+            path,
+            host: field("host"),
+            service_name,
+            header_type: if header_type == "none" {
+                String::new()
+            } else {
+                header_type
+            },
+            mode,
+            sni: field("sni"),
+            alpn: split_list(&field("alpn")),
+            fingerprint: field("fp"),
+            ..Default::default()
+        },
+    })
+}
+
+fn parse_ss_link(payload: &str) -> Result<ShareLink, MyError> {
+    let (payload, remark) = match payload.split_once('#') {
+        Some((payload, remark)) => (payload, decode(remark)),
+        None => (payload, String::new()),
+    };
+    let (payload, query) = match payload.split_once('?') {
+        Some((payload, query)) => (payload, query),
+        None => (payload, ""),
+    };
+    let invalid = || MyError::CustomError("Invalid shadowsocks link".to_string());
+
+    // SIP002 ("ss://<userinfo>@host:port") or legacy ("ss://base64(method:password@host:port)").
+    let (user_info, server) = match payload.rsplit_once('@') {
+        Some((user_info, server)) => {
+            let user_info = match decode_base64(user_info) {
+                Some(bytes) => String::from_utf8(bytes).map_err(|_| invalid())?,
+                None => decode(user_info),
+            };
+            (user_info, server.to_string())
+        }
+        None => {
+            let decoded = decode_base64(payload).ok_or_else(invalid)?;
+            let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+            let (user_info, server) = decoded.rsplit_once('@').ok_or_else(invalid)?;
+            (user_info.to_string(), server.to_string())
+        }
+    };
+
+    let (method, password) = user_info.split_once(':').ok_or_else(invalid)?;
+    let (host, port) = server.rsplit_once(':').ok_or_else(invalid)?;
+    let network = url::form_urlencoded::parse(query.as_bytes())
+        .find(|(key, _)| key == "type")
+        .map(|(_, value)| value.into_owned())
+        .unwrap_or_else(|| "tcp".to_string());
+
+    Ok(ShareLink {
+        protocol: Protocol::Shadowsocks,
+        address: host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string(),
+        port: port.trim_end_matches('/').parse()?,
+        credential: password.to_string(),
+        method: method.to_string(),
+        flow: String::new(),
+        remark,
+        stream: StreamParams {
+            network,
+            security: "none".to_string(),
+            ..Default::default()
+        },
+    })
+}
+
+/// Decodes base64 as found in share links and subscriptions: standard or URL-safe
+/// alphabet, with or without padding, ignoring embedded whitespace.
+pub(crate) fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    use base64::engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+
+    let compact: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    [STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD]
+        .iter()
+        .find_map(|engine| engine.decode(&compact).ok())
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn decode(value: &str) -> String {
+    percent_decode_str(value).decode_utf8_lossy().into_owned()
+}
+
+/// Characters escaped in link components: everything except RFC 3986 unreserved characters.
+const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+fn encode(value: &str) -> String {
+    utf8_percent_encode(value, COMPONENT).to_string()
+}
+
+fn encode_query(query: &[(&str, String)]) -> String {
+    query
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, encode(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
@@ -0,0 +1,126 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::errors::MyError;
+
+/// Category of a failed panel request, as reported to [`RequestMetrics`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The panel could not be reached (DNS, connection refused, TLS, ...).
+    Connect,
+    /// The request timed out.
+    Timeout,
+    /// The panel answered with a non-success HTTP status.
+    Status,
+    /// Logging in failed, or the session expired without credentials to log in again.
+    Auth,
+    /// The response body could not be decoded.
+    Decode,
+    /// Any other error.
+    Other,
+}
+
+impl ErrorKind {
+    /// Returns the kind as a short lowercase name, suitable as a metric label.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::Connect => "connect",
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::Status => "status",
+            ErrorKind::Auth => "auth",
+            ErrorKind::Decode => "decode",
+            ErrorKind::Other => "other",
+        }
+    }
+
+    /// Classifies an error returned by a request.
+    pub fn of(error: &MyError) -> Self {
+        match error {
+            MyError::ReqwestError(err) if err.is_timeout() => ErrorKind::Timeout,
+            MyError::ReqwestError(err) if err.is_connect() => ErrorKind::Connect,
+            MyError::ReqwestError(err) if err.is_status() => ErrorKind::Status,
+            MyError::ReqwestError(err) if err.is_decode() => ErrorKind::Decode,
+            MyError::SerdeJsonError(_) => ErrorKind::Decode,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One request sent to a panel by [`XUiClient`](crate::api::XUiClient).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestRecord {
+    /// Host of the panel.
+    pub host: String,
+    /// HTTP method, e.g. `GET`.
+    pub method: String,
+    /// Path of the endpoint, e.g. `/panel/api/inbounds/list`.
+    pub endpoint: String,
+    /// HTTP status of the last response, `None` if no response was received.
+    pub status: Option<u16>,
+    /// Time from the start of the request to the end of the response, logins included.
+    pub latency: Duration,
+    /// Number of times the request was sent again after the session was rejected.
+    pub retries: u32,
+    /// Whether the client had to log in to send the request.
+    pub relogin: bool,
+    /// Category of the error, `None` if the request succeeded.
+    pub error: Option<ErrorKind>,
+}
+
+impl RequestRecord {
+    /// Returns `true` if the request succeeded.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Hook receiving a [`RequestRecord`] for every request sent to the panel, e.g. to count
+/// requests and errors or to feed latency histograms.
+///
+/// Installed with [`XUiClient::with_metrics`](crate::api::XUiClient::with_metrics). Every
+/// request is also traced in an `xui_request` span carrying the same fields.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use xui_rs::api::XUiClient;
+/// use xui_rs::metrics::{RequestMetrics, RequestRecord};
+///
+/// #[derive(Default)]
+/// struct Counters {
+///     requests: AtomicU64,
+///     errors: AtomicU64,
+/// }
+///
+/// impl RequestMetrics for Counters {
+///     fn record(&self, request: &RequestRecord) {
+///         self.requests.fetch_add(1, Ordering::Relaxed);
+///         if !request.is_success() {
+///             self.errors.fetch_add(1, Ordering::Relaxed);
+///         }
+///     }
+/// }
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let counters = Arc::new(Counters::default());
+///     let mut client = XUiClient::new("https://your-xui-panel.com/")?
+///         .with_credentials("admin", "password")
+///         .with_metrics(counters.clone());
+///
+///     client.get_inbounds().await?;
+///     println!("{} requests", counters.requests.load(Ordering::Relaxed));
+///     Ok(())
+/// }
+/// ```
+pub trait RequestMetrics: Send + Sync {
+    /// Called once per request, after it completed or failed.
+    fn record(&self, request: &RequestRecord);
+}
@@ -0,0 +1,259 @@
+use crate::api::XUiClient;
+use crate::errors::MyError;
+use crate::fleet::Fleet;
+use crate::keys;
+use crate::models::{Client, Inbound, Protocol, ensure_success};
+
+/// Options of [`migrate_client`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrateOptions {
+    transfer_remaining_quota: bool,
+    delete_source: bool,
+}
+
+impl Default for MigrateOptions {
+    fn default() -> Self {
+        Self {
+            transfer_remaining_quota: true,
+            delete_source: false,
+        }
+    }
+}
+
+impl MigrateOptions {
+    /// Creates the default options: the remaining quota is transferred and the source
+    /// client is kept.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// When enabled (the default), the new client's quota is what the source client had
+    /// left, since traffic counters cannot be set through the API. Otherwise the quota is
+    /// copied as is and the new client starts with a full quota.
+    pub fn transfer_remaining_quota(mut self, transfer: bool) -> Self {
+        self.transfer_remaining_quota = transfer;
+        self
+    }
+
+    /// When enabled, the source client is deleted once the new one exists.
+    pub fn delete_source(mut self, delete: bool) -> Self {
+        self.delete_source = delete;
+        self
+    }
+}
+
+/// Result of a successful [`migrate_client`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigratedClient {
+    /// The client as created in the target inbound.
+    pub client: Client,
+    /// Traffic used by the source client (upload + download) when it was migrated.
+    pub used: u64,
+    pub source_deleted: bool,
+}
+
+/// Copies a client to another inbound, possibly on another panel and with another
+/// protocol, keeping its email, expiry, IP limit, subscription ID and other settings.
+///
+/// The UUID or password is kept when the target protocol can use it (a VMess/VLESS UUID
+/// also works as a Trojan password); otherwise a new credential is generated. `flow` is
+/// only kept for VLESS targets.
+///
+/// Emails are unique per panel, so migrating within one panel (pass a clone of the same
+/// client as `to`) requires `delete_source`: the source is then deleted before the copy is
+/// created.
+///
+/// # Rollback
+///
+/// If a step fails after the panel was changed, the change is undone: the new client is
+/// deleted, or the deleted source client is added back (with its traffic counters reset by
+/// the panel). The returned error describes both the failure and the rollback outcome.
+///
+/// # Example
+///
+/// ```rust
+/// use xui_rs::api::XUiClient;
+/// use xui_rs::migrate::{MigrateOptions, migrate_client};
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let mut old = XUiClient::new("https://old-xui-panel.com/")?;
+///     old.login("admin", "password").await?;
+///     let mut new = XUiClient::new("https://new-xui-panel.com/")?;
+///     new.login("admin", "password").await?;
+///
+///     let options = MigrateOptions::new().delete_source(true);
+///     let migrated =
+///         migrate_client((&mut old, 3, "user@example.com"), (&mut new, 1), &options).await?;
+///     println!("Remaining quota: {} bytes", migrated.client.total_gb);
+///     Ok(())
+/// }
+/// ```
+pub async fn migrate_client(
+    from: (&mut XUiClient, u64, &str),
+    to: (&mut XUiClient, u64),
+    options: &MigrateOptions,
+) -> Result<MigratedClient, MyError> {
+    let (source_panel, source_id, email) = from;
+    let (target_panel, target_id) = to;
+
+    let same_panel = source_panel.panel_base_url() == target_panel.panel_base_url();
+    if same_panel && !options.delete_source {
+        return Err(MyError::CustomError(
+            "Migrating within one panel requires deleting the source client, as emails are unique per panel"
+                .to_string(),
+        ));
+    }
+
+    let source_inbound = source_panel.get_inbound_typed(source_id).await?;
+    let source = source_inbound
+        .clients()?
+        .into_iter()
+        .find(|client| client.email == email)
+        .ok_or_else(|| {
+            MyError::CustomError(format!(
+                "Client '{}' not found in inbound {}",
+                email, source_id
+            ))
+        })?;
+    let source_key = source
+        .key(&source_inbound.protocol)
+        .unwrap_or(source.email.as_str())
+        .to_string();
+    let used = source_inbound
+        .client_traffic(email)
+        .map(|traffic| traffic.used())
+        .unwrap_or(0);
+
+    let target_inbound = target_panel.get_inbound_typed(target_id).await?;
+    let mut migrated = convert_client(&source, &target_inbound);
+    if options.transfer_remaining_quota && source.total_gb > 0 {
+        // A quota of 0 would mean unlimited, so an exhausted client keeps a single byte
+        migrated.total_gb = source.total_gb.saturating_sub(used).max(1);
+    }
+
+    if same_panel {
+        delete_client(source_panel, source_id, &source_key).await?;
+        if let Err(err) = add_client(target_panel, target_id, &migrated).await {
+            let rollback = add_client(source_panel, source_id, &source).await;
+            return Err(rollback_error(err, "re-adding the source client", rollback));
+        }
+    } else {
+        add_client(target_panel, target_id, &migrated).await?;
+        if options.delete_source
+            && let Err(err) = delete_client(source_panel, source_id, &source_key).await
+        {
+            let target_key = migrated
+                .key(&target_inbound.protocol)
+                .unwrap_or(migrated.email.as_str())
+                .to_string();
+            let rollback = delete_client(target_panel, target_id, &target_key).await;
+            return Err(rollback_error(err, "deleting the new client", rollback));
+        }
+    }
+
+    Ok(MigratedClient {
+        client: migrated,
+        used,
+        source_deleted: options.delete_source,
+    })
+}
+
+impl Fleet {
+    /// Migrates a client between two panels of the fleet, addressed by name, see
+    /// [`migrate_client`].
+    ///
+    /// Both ends may name the same panel.
+    pub async fn migrate_client(
+        &mut self,
+        from: (&str, u64, &str),
+        to: (&str, u64),
+        options: &MigrateOptions,
+    ) -> Result<MigratedClient, MyError> {
+        let mut target = self
+            .panel(to.0)
+            .ok_or_else(|| MyError::CustomError(format!("Unknown panel '{}'", to.0)))?
+            .clone();
+        let source = self
+            .panel(from.0)
+            .ok_or_else(|| MyError::CustomError(format!("Unknown panel '{}'", from.0)))?;
+
+        migrate_client((source, from.1, from.2), (&mut target, to.1), options).await
+    }
+}
+
+/// Builds the client to create in `target` from `source`, adapting its credential.
+fn convert_client(source: &Client, target: &Inbound) -> Client {
+    let mut client = source.clone();
+    client.id = None;
+    client.password = None;
+    client.method = None;
+
+    match &target.protocol {
+        Protocol::Vmess | Protocol::Vless => {
+            let id = source
+                .id
+                .clone()
+                .or_else(|| source.password.clone())
+                .filter(|id| uuid::Uuid::parse_str(id).is_ok());
+            client.id = Some(id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()));
+        }
+        Protocol::Trojan => {
+            let password = source.password.clone().or_else(|| source.id.clone());
+            client.password = Some(password.unwrap_or_else(keys::random_password));
+        }
+        Protocol::Shadowsocks => {
+            let method = target
+                .settings
+                .get("method")
+                .and_then(|method| method.as_str())
+                .unwrap_or_default();
+            client.password = Some(if method.starts_with("2022-") {
+                keys::shadowsocks_2022_key(method)
+            } else {
+                source
+                    .password
+                    .clone()
+                    .unwrap_or_else(keys::random_password)
+            });
+            if source.method.is_some() && !method.starts_with("2022-") {
+                client.method = source.method.clone();
+            }
+        }
+        _ => {}
+    }
+
+    if target.protocol != Protocol::Vless {
+        client.flow.clear();
+    }
+
+    client
+}
+
+async fn add_client(
+    panel: &mut XUiClient,
+    inbound_id: u64,
+    client: &Client,
+) -> Result<(), MyError> {
+    let response = panel
+        .add_client(inbound_id, serde_json::to_value(client)?)
+        .await?;
+    ensure_success(&response)
+}
+
+async fn delete_client(panel: &mut XUiClient, inbound_id: u64, key: &str) -> Result<(), MyError> {
+    let response = panel.delete_client(inbound_id, key).await?;
+    ensure_success(&response)
+}
+
+fn rollback_error(err: MyError, action: &str, rollback: Result<(), MyError>) -> MyError {
+    match rollback {
+        Ok(()) => MyError::CustomError(format!(
+            "Migration failed: {} (rolled back by {})",
+            err, action
+        )),
+        Err(rollback_err) => MyError::CustomError(format!(
+            "Migration failed: {}; rollback by {} also failed: {}",
+            err, action, rollback_err
+        )),
+    }
+}
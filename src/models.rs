@@ -0,0 +1,484 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::errors::MyError;
+
+/// Generic envelope returned by every 3X-UI API endpoint.
+///
+/// The panel always answers with `{"success": bool, "msg": string, "obj": ...}`;
+/// `obj` is absent or `null` for endpoints that only report a status.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiResponse<T> {
+    #[serde(default)]
+    pub success: bool,
+    #[serde(default)]
+    pub msg: String,
+    #[serde(default = "Option::default")]
+    pub obj: Option<T>,
+}
+
+impl<T> ApiResponse<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    /// Parses a raw JSON response returned by `XUiClient` into a typed envelope.
+    pub fn from_value(value: serde_json::Value) -> Result<Self, MyError> {
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Returns the `obj` payload if the panel reported success, or an error with its message otherwise.
+    pub fn into_result(self) -> Result<T, MyError> {
+        match self.into_optional()? {
+            Some(obj) => Ok(obj),
+            None => Err(MyError::CustomError(
+                "Panel response does not contain an object".to_string(),
+            )),
+        }
+    }
+
+    /// Like [`ApiResponse::into_result`], but accepts a missing or `null` payload.
+    pub fn into_optional(self) -> Result<Option<T>, MyError> {
+        if !self.success {
+            return Err(MyError::CustomError(format!(
+                "Panel reported failure: {}",
+                self.msg
+            )));
+        }
+
+        Ok(self.obj)
+    }
+}
+
+/// Protocol of an inbound as stored by 3X-UI.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Protocol {
+    Vmess,
+    Vless,
+    Trojan,
+    Shadowsocks,
+    DokodemoDoor,
+    Socks,
+    Http,
+    Wireguard,
+    Other(String),
+}
+
+impl Protocol {
+    /// Returns the protocol name as used in the panel and in Xray configs.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Protocol::Vmess => "vmess",
+            Protocol::Vless => "vless",
+            Protocol::Trojan => "trojan",
+            Protocol::Shadowsocks => "shadowsocks",
+            Protocol::DokodemoDoor => "dokodemo-door",
+            Protocol::Socks => "socks",
+            Protocol::Http => "http",
+            Protocol::Wireguard => "wireguard",
+            Protocol::Other(name) => name,
+        }
+    }
+
+    /// Returns `true` for protocols whose settings carry a `clients` array.
+    pub fn has_clients(&self) -> bool {
+        matches!(
+            self,
+            Protocol::Vmess | Protocol::Vless | Protocol::Trojan | Protocol::Shadowsocks
+        )
+    }
+}
+
+impl From<String> for Protocol {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "vmess" => Protocol::Vmess,
+            "vless" => Protocol::Vless,
+            "trojan" => Protocol::Trojan,
+            "shadowsocks" => Protocol::Shadowsocks,
+            "dokodemo-door" => Protocol::DokodemoDoor,
+            "socks" => Protocol::Socks,
+            "http" => Protocol::Http,
+            "wireguard" => Protocol::Wireguard,
+            _ => Protocol::Other(value),
+        }
+    }
+}
+
+impl From<Protocol> for String {
+    fn from(value: Protocol) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl Default for Protocol {
+    fn default() -> Self {
+        Protocol::Other(String::new())
+    }
+}
+
+impl std::fmt::Display for Protocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Traffic counters of a single client, as found in `clientStats` or returned by `getClientTraffics`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientTraffic {
+    #[serde(default)]
+    pub id: u64,
+    #[serde(default)]
+    pub inbound_id: u64,
+    #[serde(default)]
+    pub enable: bool,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub up: u64,
+    #[serde(default)]
+    pub down: u64,
+    /// Unix timestamp in milliseconds; `0` means unlimited, negative values mean
+    /// "duration in milliseconds counted from the first connection".
+    #[serde(default)]
+    pub expiry_time: i64,
+    /// Traffic quota in bytes; `0` means unlimited.
+    #[serde(default)]
+    pub total: u64,
+    #[serde(default)]
+    pub reset: u32,
+    /// Unix timestamp in milliseconds of the last seen activity (newer panels only).
+    #[serde(default)]
+    pub last_online: i64,
+}
+
+impl ClientTraffic {
+    /// Total amount of traffic used by the client, in bytes.
+    pub fn used(&self) -> u64 {
+        self.up.saturating_add(self.down)
+    }
+}
+
+/// A client entry of an inbound's `settings.clients` array.
+///
+/// Fields not modelled explicitly (`alterId`, `tgId`, timestamps, ...) are kept in `extra`
+/// so that a client read from the panel can be sent back without losing data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Client {
+    /// UUID of VMess/VLESS clients.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Password of Trojan/Shadowsocks clients.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub flow: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(default)]
+    pub limit_ip: u32,
+    /// Traffic quota in bytes despite the name; `0` means unlimited.
+    #[serde(default, rename = "totalGB")]
+    pub total_gb: u64,
+    /// Same convention as [`ClientTraffic::expiry_time`].
+    #[serde(default)]
+    pub expiry_time: i64,
+    #[serde(default = "default_true")]
+    pub enable: bool,
+    #[serde(default)]
+    pub sub_id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub comment: String,
+    #[serde(default)]
+    pub reset: u32,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Client {
+    /// Returns the identifier the panel uses to address this client in
+    /// `updateClient`/`delClient` for the given protocol: the UUID for VMess/VLESS,
+    /// the password for Trojan and the email for Shadowsocks.
+    pub fn key(&self, protocol: &Protocol) -> Option<&str> {
+        let key = match protocol {
+            Protocol::Vmess | Protocol::Vless => self.id.as_deref(),
+            Protocol::Trojan => self.password.as_deref(),
+            Protocol::Shadowsocks => Some(self.email.as_str()),
+            _ => None,
+        };
+
+        key.filter(|key| !key.is_empty())
+    }
+
+    /// Fills in the credential the panel requires for `protocol`, if missing: a random
+    /// UUID for VMess/VLESS and a random password for Trojan.
+    pub fn generate_credentials(&mut self, protocol: &Protocol) {
+        match protocol {
+            Protocol::Vmess | Protocol::Vless if self.id.is_none() => {
+                self.id = Some(uuid::Uuid::new_v4().to_string());
+            }
+            Protocol::Trojan if self.password.is_none() => {
+                self.password = Some(crate::keys::random_password());
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self {
+            id: None,
+            password: None,
+            email: String::new(),
+            flow: String::new(),
+            method: None,
+            limit_ip: 0,
+            total_gb: 0,
+            expiry_time: 0,
+            enable: true,
+            sub_id: String::new(),
+            comment: String::new(),
+            reset: 0,
+            extra: Map::new(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// An inbound as returned by `panel/api/inbounds/list`.
+///
+/// `settings`, `streamSettings`, `sniffing` and `allocate` are transported by the panel as
+/// JSON-encoded strings; they are decoded into `serde_json::Value` here and encoded back
+/// on serialization.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Inbound {
+    #[serde(default)]
+    pub id: u64,
+    #[serde(default)]
+    pub up: u64,
+    #[serde(default)]
+    pub down: u64,
+    #[serde(default)]
+    pub total: u64,
+    #[serde(default)]
+    pub remark: String,
+    #[serde(default)]
+    pub enable: bool,
+    #[serde(default)]
+    pub expiry_time: i64,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub client_stats: Vec<ClientTraffic>,
+    #[serde(default)]
+    pub listen: String,
+    #[serde(default)]
+    pub port: u16,
+    #[serde(default)]
+    pub protocol: Protocol,
+    #[serde(default, with = "json_string")]
+    pub settings: Value,
+    #[serde(default, with = "json_string")]
+    pub stream_settings: Value,
+    #[serde(default)]
+    pub tag: String,
+    #[serde(default, with = "json_string")]
+    pub sniffing: Value,
+    #[serde(default, with = "json_string", skip_serializing_if = "Value::is_null")]
+    pub allocate: Value,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Inbound {
+    /// Decodes the `settings.clients` array of the inbound.
+    ///
+    /// Returns an empty list for protocols without clients.
+    pub fn clients(&self) -> Result<Vec<Client>, MyError> {
+        match self.settings.get("clients") {
+            Some(clients) if !clients.is_null() => Ok(serde_json::from_value(clients.clone())?),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Replaces the `settings.clients` array of the inbound.
+    pub fn set_clients(&mut self, clients: &[Client]) -> Result<(), MyError> {
+        if !self.settings.is_object() {
+            self.settings = Value::Object(Map::new());
+        }
+        self.settings["clients"] = serde_json::to_value(clients)?;
+        Ok(())
+    }
+
+    /// Returns the traffic counters of the client with the given email, if present.
+    pub fn client_traffic(&self, email: &str) -> Option<&ClientTraffic> {
+        self.client_stats.iter().find(|stats| stats.email == email)
+    }
+
+    /// Returns the transport (`network`) of the inbound, `tcp` if unset.
+    pub fn network(&self) -> &str {
+        self.stream_settings
+            .get("network")
+            .and_then(Value::as_str)
+            .unwrap_or("tcp")
+    }
+
+    /// Returns the security layer (`none`, `tls` or `reality`) of the inbound.
+    pub fn security(&self) -> &str {
+        self.stream_settings
+            .get("security")
+            .and_then(Value::as_str)
+            .unwrap_or("none")
+    }
+}
+
+/// Tag the panel assigns to an inbound by default.
+pub(crate) fn default_tag(listen: &str, port: u16) -> String {
+    if listen.is_empty() || listen == "0.0.0.0" || listen == "::" {
+        format!("inbound-{}", port)
+    } else {
+        format!("inbound-{}:{}", listen, port)
+    }
+}
+
+/// Status of the server running the panel, as returned by `server/status`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ServerStatus {
+    /// CPU usage in percent.
+    pub cpu: f64,
+    pub cpu_cores: u32,
+    pub logical_pro: u32,
+    pub cpu_speed_mhz: f64,
+    pub mem: ResourceUsage,
+    pub swap: ResourceUsage,
+    pub disk: ResourceUsage,
+    pub xray: XrayStatus,
+    /// Uptime of the server in seconds.
+    pub uptime: u64,
+    /// Load averages over 1, 5 and 15 minutes.
+    pub loads: Vec<f64>,
+    pub tcp_count: u64,
+    pub udp_count: u64,
+    /// Current network throughput in bytes per second.
+    #[serde(rename = "netIO")]
+    pub net_io: NetIo,
+    /// Total network traffic since boot in bytes.
+    pub net_traffic: NetTraffic,
+    #[serde(rename = "publicIP")]
+    pub public_ip: PublicIp,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Used and total amount of a resource (memory, swap, disk), in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceUsage {
+    pub current: u64,
+    pub total: u64,
+}
+
+/// State of the Xray process managed by the panel.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct XrayStatus {
+    /// `running`, `stop` or `error`.
+    pub state: String,
+    pub error_msg: String,
+    pub version: String,
+}
+
+impl XrayStatus {
+    /// Returns `true` if Xray is running.
+    pub fn is_running(&self) -> bool {
+        self.state == "running"
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetIo {
+    pub up: u64,
+    pub down: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetTraffic {
+    pub sent: u64,
+    pub recv: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PublicIp {
+    pub ipv4: String,
+    pub ipv6: String,
+}
+
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// (De)serializes a `serde_json::Value` stored by the panel as a JSON-encoded string.
+mod json_string {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use serde_json::Value;
+
+    pub fn serialize<S>(value: &Value, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if value.is_null() {
+            serializer.serialize_str("")
+        } else {
+            serializer.serialize_str(&value.to_string())
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Value::deserialize(deserializer)? {
+            Value::String(s) if s.trim().is_empty() => Ok(Value::Null),
+            Value::String(s) => serde_json::from_str(&s).map_err(D::Error::custom),
+            other => Ok(other),
+        }
+    }
+}
+
+/// Parses the raw response of `XUiClient::get_inbounds` into typed inbounds.
+pub fn parse_inbounds(response: serde_json::Value) -> Result<Vec<Inbound>, MyError> {
+    let inbounds = ApiResponse::<Vec<Inbound>>::from_value(response)?.into_optional()?;
+    Ok(inbounds.unwrap_or_default())
+}
+
+/// Checks the `success` flag of a raw panel response, turning failures into errors
+/// carrying the panel's message.
+pub fn ensure_success(response: &serde_json::Value) -> Result<(), MyError> {
+    if response.get("success").and_then(Value::as_bool) == Some(true) {
+        return Ok(());
+    }
+
+    let msg = response
+        .get("msg")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    Err(MyError::CustomError(format!(
+        "Panel reported failure: {}",
+        msg
+    )))
+}
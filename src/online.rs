@@ -0,0 +1,209 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::time::Duration;
+
+use futures::Stream;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::time::MissedTickBehavior;
+
+use crate::api::XUiClient;
+use crate::errors::MyError;
+use crate::models::ApiResponse;
+use crate::ops::now_millis;
+
+/// Default interval between two polls of [`OnlineWatcher`].
+pub const DEFAULT_ONLINE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A change of a client's online state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type")]
+pub enum OnlineEvent {
+    /// The client appeared in the online list. `timestamp` is the time of the poll, in
+    /// Unix milliseconds.
+    ClientConnected { email: String, timestamp: i64 },
+    /// The client left the online list. `timestamp` is its `lastOnline` when the panel
+    /// reports it, the time of the poll otherwise, in Unix milliseconds.
+    ClientDisconnected { email: String, timestamp: i64 },
+}
+
+impl OnlineEvent {
+    /// Returns the email of the client.
+    pub fn email(&self) -> &str {
+        match self {
+            OnlineEvent::ClientConnected { email, .. }
+            | OnlineEvent::ClientDisconnected { email, .. } => email,
+        }
+    }
+
+    /// Returns the time of the event, as a Unix timestamp in milliseconds.
+    pub fn timestamp(&self) -> i64 {
+        match self {
+            OnlineEvent::ClientConnected { timestamp, .. }
+            | OnlineEvent::ClientDisconnected { timestamp, .. } => *timestamp,
+        }
+    }
+}
+
+/// Parses the response of `get_online_clients` into the emails of the online clients.
+///
+/// Panels return a list of emails; entries with an `email` field are accepted as well.
+pub fn parse_online_clients(response: Value) -> Result<Vec<String>, MyError> {
+    Ok(ApiResponse::<Vec<Value>>::from_value(response)?
+        .into_optional()?
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|entry| entry.as_str().or_else(|| entry["email"].as_str()))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Turns successive online lists into connect/disconnect events.
+///
+/// This is the state machine behind [`OnlineWatcher`]. Clients already online at the
+/// first update are reported as connected.
+#[derive(Debug, Clone, Default)]
+pub struct OnlineTracker {
+    online: BTreeSet<String>,
+}
+
+impl OnlineTracker {
+    /// Creates a tracker with no client online.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the clients currently online, sorted by email.
+    pub fn online(&self) -> impl Iterator<Item = &str> {
+        self.online.iter().map(String::as_str)
+    }
+
+    /// Records the clients online at `now` (Unix milliseconds) and returns the changes,
+    /// disconnections first.
+    ///
+    /// `last_online` gives the `lastOnline` timestamps of the clients, used as time of
+    /// their disconnection.
+    pub fn update(
+        &mut self,
+        online: &[String],
+        last_online: &HashMap<String, i64>,
+        now: i64,
+    ) -> Vec<OnlineEvent> {
+        let online: BTreeSet<String> = online.iter().cloned().collect();
+
+        let disconnected =
+            self.online
+                .difference(&online)
+                .map(|email| OnlineEvent::ClientDisconnected {
+                    email: email.clone(),
+                    timestamp: last_online
+                        .get(email)
+                        .copied()
+                        .filter(|timestamp| *timestamp > 0)
+                        .unwrap_or(now),
+                });
+        let connected = online
+            .difference(&self.online)
+            .map(|email| OnlineEvent::ClientConnected {
+                email: email.clone(),
+                timestamp: now,
+            });
+        let events = disconnected.chain(connected).collect();
+
+        self.online = online;
+        events
+    }
+
+    /// Returns `true` if a client currently online is missing from `online`.
+    fn has_disconnections(&self, online: &[String]) -> bool {
+        self.online.iter().any(|email| !online.contains(email))
+    }
+}
+
+/// Polls the online clients of a panel and reports who connects and disconnects, so
+/// consumers do not have to diff the lists of `get_online_clients` themselves.
+///
+/// When clients disconnect, their `lastOnline` is read from the client stats of the
+/// inbounds to timestamp the disconnection more precisely than the polling interval.
+///
+/// # Example
+///
+/// ```rust
+/// use futures::StreamExt;
+/// use xui_rs::api::XUiClient;
+/// use xui_rs::online::{OnlineEvent, OnlineWatcher};
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let client = XUiClient::new("https://your-xui-panel.com/")?
+///         .with_credentials("admin", "password");
+///
+///     let mut events = Box::pin(OnlineWatcher::new(client).into_stream());
+///     while let Some(event) = events.next().await {
+///         match event? {
+///             OnlineEvent::ClientConnected { email, .. } => println!("{} connected", email),
+///             OnlineEvent::ClientDisconnected { email, .. } => println!("{} left", email),
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct OnlineWatcher {
+    client: XUiClient,
+    interval: Duration,
+    tracker: OnlineTracker,
+}
+
+impl OnlineWatcher {
+    /// Creates a watcher polling the panel every [`DEFAULT_ONLINE_INTERVAL`].
+    pub fn new(client: XUiClient) -> Self {
+        Self {
+            client,
+            interval: DEFAULT_ONLINE_INTERVAL,
+            tracker: OnlineTracker::new(),
+        }
+    }
+
+    /// Sets the interval between two polls (at least 1 ms).
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval.max(Duration::from_millis(1));
+        self
+    }
+
+    /// Fetches the online clients once and returns the changes since the previous poll.
+    pub async fn poll(&mut self) -> Result<Vec<OnlineEvent>, MyError> {
+        let online = parse_online_clients(self.client.get_online_clients().await?)?;
+
+        let mut last_online = HashMap::new();
+        if self.tracker.has_disconnections(&online) {
+            for inbound in self.client.get_inbounds_typed().await? {
+                for stats in inbound.client_stats {
+                    last_online.insert(stats.email, stats.last_online);
+                }
+            }
+        }
+
+        Ok(self.tracker.update(&online, &last_online, now_millis()))
+    }
+
+    /// Polls forever at the configured interval, starting immediately, and yields the
+    /// events one by one (or the error of a failed poll).
+    pub fn into_stream(self) -> impl Stream<Item = Result<OnlineEvent, MyError>> {
+        let mut ticker = tokio::time::interval(self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        futures::stream::unfold(
+            (self, ticker, VecDeque::new()),
+            |(mut watcher, mut ticker, mut pending)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((Ok(event), (watcher, ticker, pending)));
+                    }
+                    ticker.tick().await;
+                    match watcher.poll().await {
+                        Ok(events) => pending.extend(events),
+                        Err(err) => return Some((Err(err), (watcher, ticker, pending))),
+                    }
+                }
+            },
+        )
+    }
+}
@@ -0,0 +1,186 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::api::XUiClient;
+use crate::errors::MyError;
+use crate::models::{Client, ensure_success};
+
+pub(crate) const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// Returns the current time as a Unix timestamp in milliseconds, the unit used by the panel.
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_millis() as i64
+}
+
+/// Computes the `expiryTime` of a client whose subscription is extended by `days`.
+///
+/// Follows the panel's conventions:
+/// - `0` (never expires) is left unchanged;
+/// - negative values ("start on first use", the absolute value being the duration in
+///   milliseconds) get a longer duration;
+/// - a past expiry is extended from `now`, a future one from its current value.
+pub fn extended_expiry(current: i64, days: u32, now: i64) -> i64 {
+    let extension = i64::from(days) * MILLIS_PER_DAY;
+
+    match current {
+        0 => 0,
+        current if current < 0 => current - extension,
+        current => current.max(now) + extension,
+    }
+}
+
+/// Computes the `expiryTime` of a client starting a new period of `days`.
+///
+/// Follows the same conventions as [`extended_expiry`]:
+/// - `0` (never expires) is left unchanged, and so is any expiry when `days` is `0`;
+/// - clients still waiting for their first connection keep the "start on first use"
+///   convention with the new duration;
+/// - others expire `days` after `now`.
+pub fn renewed_expiry(current: i64, days: u32, now: i64) -> i64 {
+    let period = i64::from(days) * MILLIS_PER_DAY;
+
+    match current {
+        current if current == 0 || days == 0 => current,
+        current if current < 0 => -period,
+        _ => now + period,
+    }
+}
+
+/// High-level subscription lifecycle operations, as performed by billing systems.
+///
+/// Obtained with [`XUiClient::ops`]. Every operation fetches the client's inbound, changes
+/// only the relevant fields and sends the complete client back, returning the updated entry.
+/// Clients are addressed by inbound ID and email (or UUID/Trojan password).
+///
+/// # Example
+///
+/// ```rust
+/// use xui_rs::api::XUiClient;
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+///     client.login("admin", "password").await?;
+///
+///     let mut ops = client.ops();
+///     ops.extend_expiry(3_u64, "user@example.com", 30).await?;
+///     ops.add_quota_bytes(3_u64, "user@example.com", 50 * 1024 * 1024 * 1024).await?;
+///     Ok(())
+/// }
+/// ```
+pub struct ClientOps<'a> {
+    client: &'a mut XUiClient,
+}
+
+impl XUiClient {
+    /// Returns the subscription lifecycle helpers for this panel.
+    pub fn ops(&mut self) -> ClientOps<'_> {
+        ClientOps { client: self }
+    }
+}
+
+impl ClientOps<'_> {
+    /// Extends the client's expiry by `days`, see [`extended_expiry`] for the rules.
+    pub async fn extend_expiry(
+        &mut self,
+        inbound_id: impl Into<u64>,
+        email: impl Into<String>,
+        days: u32,
+    ) -> Result<Client, MyError> {
+        let now = now_millis();
+        self.client
+            .modify_client(inbound_id, email, |client| {
+                client.expiry_time = extended_expiry(client.expiry_time, days, now);
+            })
+            .await
+    }
+
+    /// Adds `bytes` to the client's traffic quota.
+    ///
+    /// Unlimited quotas (`0`) stay unlimited.
+    pub async fn add_quota_bytes(
+        &mut self,
+        inbound_id: impl Into<u64>,
+        email: impl Into<String>,
+        bytes: u64,
+    ) -> Result<Client, MyError> {
+        self.client
+            .modify_client(inbound_id, email, |client| {
+                if client.total_gb != 0 {
+                    client.total_gb = client.total_gb.saturating_add(bytes);
+                }
+            })
+            .await
+    }
+
+    /// Disables the client without touching its quota, expiry or traffic.
+    pub async fn suspend(
+        &mut self,
+        inbound_id: impl Into<u64>,
+        email: impl Into<String>,
+    ) -> Result<Client, MyError> {
+        self.client
+            .modify_client(inbound_id, email, |client| client.enable = false)
+            .await
+    }
+
+    /// Re-enables a suspended client.
+    pub async fn resume(
+        &mut self,
+        inbound_id: impl Into<u64>,
+        email: impl Into<String>,
+    ) -> Result<Client, MyError> {
+        self.client
+            .modify_client(inbound_id, email, |client| client.enable = true)
+            .await
+    }
+
+    /// Starts a new billing period: resets the client's traffic counters, sets the expiry
+    /// `days` from now (see [`renewed_expiry`]), optionally sets a new quota in bytes and
+    /// enables the client.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `MyError` if the client could not be updated. If it was updated but its
+    /// traffic could not be reset, the error says so: the client is then renewed with the
+    /// usage of the previous period, and resetting its traffic completes the renewal.
+    pub async fn renew_period(
+        &mut self,
+        inbound_id: impl Into<u64>,
+        email: impl Into<String>,
+        days: u32,
+        quota_bytes: Option<u64>,
+    ) -> Result<Client, MyError> {
+        let id = inbound_id.into();
+        let now = now_millis();
+
+        let client = self
+            .client
+            .modify_client(id, email, |client| {
+                client.expiry_time = renewed_expiry(client.expiry_time, days, now);
+                if let Some(quota) = quota_bytes {
+                    client.total_gb = quota;
+                }
+                client.enable = true;
+            })
+            .await?;
+
+        let reset = match self
+            .client
+            .reset_client_traffic(id, client.email.clone())
+            .await
+        {
+            Ok(response) => ensure_success(&response),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = reset {
+            return Err(MyError::CustomError(format!(
+                "Client '{}' was renewed but its traffic was not reset: {}",
+                client.email, err
+            )));
+        }
+
+        Ok(client)
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+use crate::api::XUiClient;
+use crate::errors::MyError;
+use crate::models::Inbound;
+use crate::ops::now_millis;
+
+/// Default interval between two polls of [`TrafficPoller`].
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Traffic of one client during a polling interval, in bytes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientDelta {
+    pub inbound_id: u64,
+    pub email: String,
+    pub up: u64,
+    pub down: u64,
+    /// `true` if the counters went backwards since the previous poll (e.g. after
+    /// `reset_client_traffic`); the delta is then the traffic counted since the reset.
+    pub reset: bool,
+}
+
+impl ClientDelta {
+    /// Returns the total traffic (upload + download) of the interval.
+    pub fn total(&self) -> u64 {
+        self.up + self.down
+    }
+}
+
+/// Traffic of one inbound during a polling interval, in bytes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundDelta {
+    pub inbound_id: u64,
+    pub remark: String,
+    pub up: u64,
+    pub down: u64,
+    /// Same as [`ClientDelta::reset`].
+    pub reset: bool,
+}
+
+impl InboundDelta {
+    /// Returns the total traffic (upload + download) of the interval.
+    pub fn total(&self) -> u64 {
+        self.up + self.down
+    }
+}
+
+/// Traffic measured between two polls.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrafficSample {
+    /// Start of the interval, as a Unix timestamp in milliseconds.
+    pub from: i64,
+    /// End of the interval (time of the poll), as a Unix timestamp in milliseconds.
+    pub to: i64,
+    pub clients: Vec<ClientDelta>,
+    pub inbounds: Vec<InboundDelta>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Counters {
+    up: u64,
+    down: u64,
+}
+
+impl Counters {
+    /// Returns the traffic since `previous` and whether the counters were reset.
+    ///
+    /// Counters only go down when they are reset, in which case everything counted since
+    /// the reset belongs to this interval. A counter seen for the first time (new client or
+    /// inbound) counts entirely.
+    fn delta(self, previous: Option<Counters>) -> (u64, u64, bool) {
+        match previous {
+            Some(previous) if self.up < previous.up || self.down < previous.down => {
+                (self.up, self.down, true)
+            }
+            Some(previous) => (self.up - previous.up, self.down - previous.down, false),
+            None => (self.up, self.down, false),
+        }
+    }
+}
+
+/// Computes per-interval deltas from successive snapshots of cumulative counters.
+///
+/// This is the state machine behind [`TrafficPoller`], usable on its own with inbounds
+/// fetched by other means. The first update only records a baseline.
+#[derive(Debug, Clone, Default)]
+pub struct TrafficTracker {
+    last_poll: Option<i64>,
+    clients: HashMap<(u64, String), Counters>,
+    inbounds: HashMap<u64, Counters>,
+}
+
+impl TrafficTracker {
+    /// Creates a tracker without baseline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the counters of `inbounds` observed at `timestamp` (Unix milliseconds).
+    ///
+    /// # Returns
+    ///
+    /// The traffic since the previous update, or `None` for the first update. Clients and
+    /// inbounds that disappeared are forgotten.
+    pub fn update(&mut self, inbounds: &[Inbound], timestamp: i64) -> Option<TrafficSample> {
+        let mut clients = HashMap::new();
+        let mut inbound_counters = HashMap::new();
+        let mut sample = TrafficSample {
+            from: self.last_poll.unwrap_or(timestamp),
+            to: timestamp,
+            clients: Vec::new(),
+            inbounds: Vec::new(),
+        };
+
+        for inbound in inbounds {
+            let counters = Counters {
+                up: inbound.up,
+                down: inbound.down,
+            };
+            let (up, down, reset) = counters.delta(self.inbounds.get(&inbound.id).copied());
+            sample.inbounds.push(InboundDelta {
+                inbound_id: inbound.id,
+                remark: inbound.remark.clone(),
+                up,
+                down,
+                reset,
+            });
+            inbound_counters.insert(inbound.id, counters);
+
+            for stats in &inbound.client_stats {
+                let key = (inbound.id, stats.email.clone());
+                let counters = Counters {
+                    up: stats.up,
+                    down: stats.down,
+                };
+                let (up, down, reset) = counters.delta(self.clients.get(&key).copied());
+                sample.clients.push(ClientDelta {
+                    inbound_id: inbound.id,
+                    email: stats.email.clone(),
+                    up,
+                    down,
+                    reset,
+                });
+                clients.insert(key, counters);
+            }
+        }
+
+        let first = self.last_poll.is_none();
+        self.last_poll = Some(timestamp);
+        self.clients = clients;
+        self.inbounds = inbound_counters;
+
+        if first { None } else { Some(sample) }
+    }
+}
+
+/// Periodically samples the client and inbound counters of a panel and turns them into
+/// per-interval [`TrafficSample`]s, e.g. for billing.
+///
+/// The panel only exposes cumulative counters, which go back to zero when traffic is
+/// reset; the poller detects resets and reports the traffic counted since. Samples are
+/// consumed as a [`Stream`] ([`TrafficPoller::into_stream`]) or through a channel fed by a
+/// background task ([`TrafficPoller::spawn`]). A failed poll yields an error and the next
+/// one carries the traffic of both intervals.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use xui_rs::api::XUiClient;
+/// use xui_rs::poller::TrafficPoller;
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let client = XUiClient::new("https://your-xui-panel.com/")?
+///         .with_credentials("admin", "password");
+///
+///     let (mut samples, _task) = TrafficPoller::new(client)
+///         .interval(Duration::from_secs(300))
+///         .spawn(16);
+///     while let Some(sample) = samples.recv().await {
+///         for client in sample?.clients {
+///             println!("{}: {} bytes", client.email, client.total());
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct TrafficPoller {
+    client: XUiClient,
+    interval: Duration,
+    tracker: TrafficTracker,
+}
+
+impl TrafficPoller {
+    /// Creates a poller sampling the panel every [`DEFAULT_POLL_INTERVAL`].
+    pub fn new(client: XUiClient) -> Self {
+        Self {
+            client,
+            interval: DEFAULT_POLL_INTERVAL,
+            tracker: TrafficTracker::new(),
+        }
+    }
+
+    /// Sets the interval between two polls (at least 1 ms).
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval.max(Duration::from_millis(1));
+        self
+    }
+
+    /// Fetches the inbounds once and returns the traffic since the previous poll, or
+    /// `None` on the first poll, which only records a baseline.
+    pub async fn poll(&mut self) -> Result<Option<TrafficSample>, MyError> {
+        let inbounds = self.client.get_inbounds_typed().await?;
+        Ok(self.tracker.update(&inbounds, now_millis()))
+    }
+
+    /// Polls forever at the configured interval, starting immediately, and yields a
+    /// sample per interval (or the error of a failed poll).
+    pub fn into_stream(self) -> impl Stream<Item = Result<TrafficSample, MyError>> {
+        let mut ticker = tokio::time::interval(self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        futures::stream::unfold((self, ticker), |(mut poller, mut ticker)| async move {
+            loop {
+                ticker.tick().await;
+                match poller.poll().await {
+                    Ok(None) => continue,
+                    Ok(Some(sample)) => return Some((Ok(sample), (poller, ticker))),
+                    Err(err) => return Some((Err(err), (poller, ticker))),
+                }
+            }
+        })
+    }
+
+    /// Runs the poller in a background task sending samples to a channel of capacity
+    /// `buffer` (at least 1). The task stops when the receiver is dropped.
+    pub fn spawn(
+        self,
+        buffer: usize,
+    ) -> (
+        mpsc::Receiver<Result<TrafficSample, MyError>>,
+        JoinHandle<()>,
+    ) {
+        let (sender, receiver) = mpsc::channel(buffer.max(1));
+        let task = tokio::spawn(async move {
+            let mut stream = Box::pin(self.into_stream());
+            while let Some(sample) = stream.next().await {
+                if sender.send(sample).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        (receiver, task)
+    }
+}
@@ -0,0 +1,107 @@
+use qrcode::render::{svg, unicode};
+use qrcode::{Color, EcLevel, QrCode};
+
+use crate::errors::MyError;
+use crate::links::ShareLink;
+
+/// Width of the light border around the code, in modules, as required by the QR spec.
+const QUIET_ZONE: usize = 4;
+
+/// A QR code of a share link or subscription URL, rendered locally.
+///
+/// Nothing is sent over the network: links carry client credentials and must not be
+/// pasted into online generators.
+///
+/// # Example
+///
+/// ```rust
+/// use xui_rs::qr::LinkQrCode;
+///
+/// fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let qr = LinkQrCode::new("https://your-xui-panel.com:2096/sub/k8xq2nz7a1b2c3d4")?;
+///     println!("{}", qr.to_terminal());
+///     std::fs::write("subscription.png", qr.to_png(8)?)?;
+///     Ok(())
+/// }
+/// ```
+pub struct LinkQrCode {
+    code: QrCode,
+}
+
+impl LinkQrCode {
+    /// Encodes arbitrary text (typically a share link or subscription URL).
+    ///
+    /// Medium error correction is used, which keeps long VLESS Reality links scannable.
+    pub fn new(data: impl AsRef<[u8]>) -> Result<Self, MyError> {
+        let code = match QrCode::with_error_correction_level(data, EcLevel::M) {
+            Ok(code) => code,
+            Err(e) => return Err(MyError::CustomError(format!("QR encoding failed: {}", e))),
+        };
+
+        Ok(Self { code })
+    }
+
+    /// Encodes the URI of a share link.
+    pub fn from_share_link(link: &ShareLink) -> Result<Self, MyError> {
+        Self::new(link.to_uri()?)
+    }
+
+    /// Returns the number of modules per side, excluding the quiet zone.
+    pub fn width(&self) -> usize {
+        self.code.width()
+    }
+
+    /// Renders the code as an 8-bit grayscale PNG where each module is
+    /// `module_size` x `module_size` pixels.
+    pub fn to_png(&self, module_size: u32) -> Result<Vec<u8>, MyError> {
+        let module_size = module_size.max(1) as usize;
+        let modules = self.code.width() + 2 * QUIET_ZONE;
+        let side = modules * module_size;
+        let colors = self.code.to_colors();
+
+        let mut pixels = vec![0xFF_u8; side * side];
+        for y in 0..self.code.width() {
+            for x in 0..self.code.width() {
+                if colors[y * self.code.width() + x] != Color::Dark {
+                    continue;
+                }
+                let top = (y + QUIET_ZONE) * module_size;
+                let left = (x + QUIET_ZONE) * module_size;
+                for row in top..top + module_size {
+                    pixels[row * side + left..row * side + left + module_size].fill(0);
+                }
+            }
+        }
+
+        let mut output = Vec::new();
+        let mut encoder = png::Encoder::new(&mut output, side as u32, side as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let png_error =
+            |e: png::EncodingError| MyError::CustomError(format!("PNG encoding failed: {}", e));
+        let mut writer = encoder.write_header().map_err(png_error)?;
+        writer.write_image_data(&pixels).map_err(png_error)?;
+        writer.finish().map_err(png_error)?;
+
+        Ok(output)
+    }
+
+    /// Renders the code as a standalone SVG document.
+    pub fn to_svg(&self, module_size: u32) -> String {
+        self.code
+            .render::<svg::Color>()
+            .module_dimensions(module_size.max(1), module_size.max(1))
+            .build()
+    }
+
+    /// Renders the code with Unicode half blocks, two modules per character row,
+    /// for printing in a terminal with light text on a dark background.
+    pub fn to_terminal(&self) -> String {
+        self.code
+            .render::<unicode::Dense1x2>()
+            .dark_color(unicode::Dense1x2::Light)
+            .light_color(unicode::Dense1x2::Dark)
+            .build()
+    }
+}
@@ -0,0 +1,683 @@
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::api::XUiClient;
+use crate::errors::MyError;
+use crate::models::{Client, Inbound, Protocol, ensure_success};
+
+/// Desired inbounds and clients of a panel, typically kept in a Git-tracked YAML or JSON file.
+///
+/// ```yaml
+/// inbounds:
+///   - remark: reality
+///     port: 443
+///     protocol: vless
+///     settings: { decryption: none }
+///     streamSettings:
+///       network: tcp
+///       security: reality
+///     clients:
+///       - email: alice
+///         id: 11111111-2222-3333-4444-555555555555
+///         flow: xtls-rprx-vision
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PanelState {
+    #[serde(default)]
+    pub inbounds: Vec<DesiredInbound>,
+}
+
+impl PanelState {
+    /// Parses a desired state from YAML.
+    pub fn from_yaml(yaml: &str) -> Result<Self, MyError> {
+        serde_yaml::from_str(yaml)
+            .map_err(|e| MyError::CustomError(format!("Invalid desired state YAML: {}", e)))
+    }
+
+    /// Parses a desired state from JSON.
+    pub fn from_json(json: &str) -> Result<Self, MyError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Loads a desired state from a file, parsed as JSON if its extension is `.json`
+    /// and as YAML otherwise.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MyError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&content),
+            _ => Self::from_yaml(&content),
+        }
+    }
+}
+
+/// Desired configuration of one inbound.
+///
+/// Inbounds are matched against the panel by `listen` address and `port`; clients are
+/// matched by email, see [`DesiredClient`]. `settings`, `streamSettings` and `sniffing` are plain objects here
+/// (not JSON-encoded strings) and are compared key by key: keys absent from the desired
+/// state are left as they are on the panel, and a missing section is not managed at all.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DesiredInbound {
+    #[serde(default)]
+    pub remark: String,
+    #[serde(default = "default_true")]
+    pub enable: bool,
+    #[serde(default)]
+    pub listen: String,
+    pub port: u16,
+    pub protocol: Protocol,
+    /// Traffic quota of the inbound in bytes; `0` means unlimited.
+    #[serde(default)]
+    pub total: u64,
+    #[serde(default)]
+    pub expiry_time: i64,
+    /// Protocol settings without the `clients` array.
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub settings: Value,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub stream_settings: Value,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub sniffing: Value,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clients: Vec<DesiredClient>,
+}
+
+/// Desired configuration of one client.
+///
+/// Only the fields declared in the desired state are managed: a client declared with its
+/// email alone keeps the quota, expiry, IP limit and state it has on the panel. Omitted
+/// fields take the [`Client`] defaults when the client is created.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Map<String, Value>", into = "Map<String, Value>")]
+pub struct DesiredClient {
+    pub client: Client,
+    fields: Map<String, Value>,
+}
+
+impl DesiredClient {
+    /// Creates a desired client managing every field of `client`.
+    pub fn new(client: Client) -> Result<Self, MyError> {
+        match serde_json::to_value(&client)? {
+            Value::Object(fields) => Ok(Self { client, fields }),
+            _ => Err(MyError::CustomError(
+                "Client did not serialize to an object".to_string(),
+            )),
+        }
+    }
+
+    /// Returns the managed fields, as named by the panel (e.g. `totalGB`).
+    pub fn fields(&self) -> &Map<String, Value> {
+        &self.fields
+    }
+}
+
+impl TryFrom<Map<String, Value>> for DesiredClient {
+    type Error = serde_json::Error;
+
+    fn try_from(fields: Map<String, Value>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            client: serde_json::from_value(Value::Object(fields.clone()))?,
+            fields,
+        })
+    }
+}
+
+impl From<DesiredClient> for Map<String, Value> {
+    fn from(desired: DesiredClient) -> Self {
+        desired.fields
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl DesiredInbound {
+    /// Describes the inbound in plan output, e.g. `:443 "reality"`.
+    fn label(&self) -> String {
+        inbound_label(&self.listen, self.port, &self.remark)
+    }
+
+    /// Builds the inbound to create, including its clients.
+    fn to_inbound(&self) -> Result<Inbound, MyError> {
+        let mut inbound = Inbound {
+            remark: self.remark.clone(),
+            enable: self.enable,
+            listen: self.listen.clone(),
+            port: self.port,
+            protocol: self.protocol.clone(),
+            total: self.total,
+            expiry_time: self.expiry_time,
+            settings: self.settings.clone(),
+            stream_settings: self.stream_settings.clone(),
+            sniffing: self.sniffing.clone(),
+            ..Default::default()
+        };
+
+        if self.protocol.has_clients() {
+            let clients: Vec<Client> = self
+                .clients
+                .iter()
+                .map(|desired| with_credentials(&desired.client, &self.protocol))
+                .collect();
+            inbound.set_clients(&clients)?;
+        }
+
+        Ok(inbound)
+    }
+}
+
+fn inbound_label(listen: &str, port: u16, remark: &str) -> String {
+    format!("{}:{} \"{}\"", listen, port, remark)
+}
+
+fn with_credentials(client: &Client, protocol: &Protocol) -> Client {
+    let mut client = client.clone();
+    client.generate_credentials(protocol);
+    client
+}
+
+/// A single field that differs between the panel and the desired state.
+///
+/// `field` is a dotted path such as `streamSettings.realitySettings.dest`; a field absent
+/// on the panel is reported as `null`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.from, self.to)
+    }
+}
+
+/// One step of a [`Plan`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    CreateInbound {
+        inbound: Inbound,
+    },
+    UpdateInbound {
+        id: u64,
+        changes: Vec<FieldChange>,
+        inbound: Inbound,
+    },
+    DeleteInbound {
+        id: u64,
+        label: String,
+    },
+    AddClient {
+        inbound_id: u64,
+        client: Client,
+    },
+    UpdateClient {
+        inbound_id: u64,
+        key: String,
+        changes: Vec<FieldChange>,
+        client: Client,
+    },
+    DeleteClient {
+        inbound_id: u64,
+        key: String,
+        email: String,
+    },
+}
+
+impl Change {
+    /// Order in which changes are applied: deletions first (freeing ports and emails),
+    /// then inbound changes, then client changes.
+    fn phase(&self) -> u8 {
+        match self {
+            Change::DeleteClient { .. } => 0,
+            Change::DeleteInbound { .. } => 1,
+            Change::UpdateInbound { .. } => 2,
+            Change::CreateInbound { .. } => 3,
+            Change::UpdateClient { .. } => 4,
+            Change::AddClient { .. } => 5,
+        }
+    }
+
+    /// Performs the change on the panel.
+    async fn apply(&self, client: &mut XUiClient) -> Result<(), MyError> {
+        let response = match self {
+            Change::CreateInbound { inbound } => {
+                client.add_inbound(serde_json::to_value(inbound)?).await?
+            }
+            Change::UpdateInbound { id, inbound, .. } => {
+                client
+                    .update_inbound(*id, serde_json::to_value(inbound)?)
+                    .await?
+            }
+            Change::DeleteInbound { id, .. } => client.delete_inbound(*id).await?,
+            Change::AddClient {
+                inbound_id,
+                client: new_client,
+            } => {
+                client
+                    .add_client(*inbound_id, serde_json::to_value(new_client)?)
+                    .await?
+            }
+            Change::UpdateClient {
+                inbound_id,
+                key,
+                client: updated,
+                ..
+            } => {
+                client
+                    .update_client(key.as_str(), *inbound_id, serde_json::to_value(updated)?)
+                    .await?
+            }
+            Change::DeleteClient {
+                inbound_id, key, ..
+            } => client.delete_client(*inbound_id, key.as_str()).await?,
+        };
+
+        ensure_success(&response)
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::CreateInbound { inbound } => {
+                write!(
+                    f,
+                    "+ inbound {} ({})",
+                    inbound_label(&inbound.listen, inbound.port, &inbound.remark),
+                    inbound.protocol
+                )?;
+                for client in inbound.clients().unwrap_or_default() {
+                    write!(f, "\n    + client {}", client.email)?;
+                }
+                Ok(())
+            }
+            Change::UpdateInbound {
+                id,
+                changes,
+                inbound,
+            } => {
+                write!(
+                    f,
+                    "~ inbound #{} {}",
+                    id,
+                    inbound_label(&inbound.listen, inbound.port, &inbound.remark)
+                )?;
+                for change in changes {
+                    write!(f, "\n    {}", change)?;
+                }
+                Ok(())
+            }
+            Change::DeleteInbound { id, label } => write!(f, "- inbound #{} {}", id, label),
+            Change::AddClient { inbound_id, client } => {
+                write!(f, "+ client {} in inbound #{}", client.email, inbound_id)
+            }
+            Change::UpdateClient {
+                inbound_id,
+                changes,
+                client,
+                ..
+            } => {
+                write!(f, "~ client {} in inbound #{}", client.email, inbound_id)?;
+                for change in changes {
+                    write!(f, "\n    {}", change)?;
+                }
+                Ok(())
+            }
+            Change::DeleteClient {
+                inbound_id, email, ..
+            } => write!(f, "- client {} in inbound #{}", email, inbound_id),
+        }
+    }
+}
+
+/// The changes needed to converge a panel to a [`PanelState`].
+///
+/// `Display` renders a human-readable plan, one line per inbound or client with
+/// indented field-level diffs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Plan {
+    pub changes: Vec<Change>,
+}
+
+impl Plan {
+    /// Returns `true` if the panel already matches the desired state.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Applies the changes in order, stopping at the first failure.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `MyError` naming the change that failed. Changes applied before it are
+    /// kept; planning again against the panel yields the remaining changes.
+    pub async fn apply(&self, client: &mut XUiClient) -> Result<(), MyError> {
+        for change in &self.changes {
+            if let Err(err) = change.apply(client).await {
+                return Err(MyError::CustomError(format!(
+                    "Failed to apply `{}`: {}",
+                    change.to_string().lines().next().unwrap_or_default(),
+                    err
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return write!(f, "No changes.");
+        }
+
+        for (i, change) in self.changes.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Converges a panel to a desired [`PanelState`] in two steps: [`Reconciler::plan`]
+/// computes the differences and [`Plan::apply`] performs them.
+///
+/// Inbounds and clients present on the panel but absent from the desired state are only
+/// deleted when pruning is enabled.
+///
+/// # Example
+///
+/// ```rust
+/// use xui_rs::api::XUiClient;
+/// use xui_rs::reconcile::{PanelState, Reconciler};
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+///     client.login("admin", "password").await?;
+///
+///     let desired = PanelState::load("panel.yaml")?;
+///     let plan = Reconciler::new(desired).prune(true).plan(&mut client).await?;
+///     println!("{}", plan);
+///
+///     plan.apply(&mut client).await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Reconciler {
+    desired: PanelState,
+    prune: bool,
+}
+
+impl Reconciler {
+    /// Creates a reconciler for the given desired state, without pruning.
+    pub fn new(desired: PanelState) -> Self {
+        Self {
+            desired,
+            prune: false,
+        }
+    }
+
+    /// When enabled, inbounds and clients missing from the desired state are deleted.
+    pub fn prune(mut self, prune: bool) -> Self {
+        self.prune = prune;
+        self
+    }
+
+    /// Fetches the panel's inbounds and plans the changes against them.
+    pub async fn plan(&self, client: &mut XUiClient) -> Result<Plan, MyError> {
+        let current = client.get_inbounds_typed().await?;
+        self.plan_against(&current)
+    }
+
+    /// Plans the changes needed to turn `current` into the desired state.
+    pub fn plan_against(&self, current: &[Inbound]) -> Result<Plan, MyError> {
+        let mut changes = Vec::new();
+        let mut matched = vec![false; current.len()];
+
+        for desired in &self.desired.inbounds {
+            let position = current.iter().position(|inbound| {
+                inbound.listen == desired.listen && inbound.port == desired.port
+            });
+
+            match position {
+                Some(index) => {
+                    if matched[index] {
+                        return Err(MyError::CustomError(format!(
+                            "Inbound {} is declared more than once",
+                            desired.label()
+                        )));
+                    }
+                    matched[index] = true;
+                    self.plan_inbound(&current[index], desired, &mut changes)?;
+                }
+                None => changes.push(Change::CreateInbound {
+                    inbound: desired.to_inbound()?,
+                }),
+            }
+        }
+
+        if self.prune {
+            for (inbound, _) in current.iter().zip(&matched).filter(|(_, m)| !**m) {
+                changes.push(Change::DeleteInbound {
+                    id: inbound.id,
+                    label: inbound_label(&inbound.listen, inbound.port, &inbound.remark),
+                });
+            }
+        }
+
+        changes.sort_by_key(Change::phase);
+        Ok(Plan { changes })
+    }
+
+    /// Plans the changes of an inbound present both on the panel and in the desired state.
+    fn plan_inbound(
+        &self,
+        current: &Inbound,
+        desired: &DesiredInbound,
+        changes: &mut Vec<Change>,
+    ) -> Result<(), MyError> {
+        let mut updated = current.clone();
+        let mut field_changes = Vec::new();
+
+        let mut compare = |field: &str, from: Value, to: Value| {
+            diff_values(field, &from, &to, &mut field_changes);
+        };
+        compare(
+            "remark",
+            current.remark.clone().into(),
+            desired.remark.clone().into(),
+        );
+        compare("enable", current.enable.into(), desired.enable.into());
+        compare(
+            "protocol",
+            current.protocol.as_str().into(),
+            desired.protocol.as_str().into(),
+        );
+        compare("total", current.total.into(), desired.total.into());
+        compare(
+            "expiryTime",
+            current.expiry_time.into(),
+            desired.expiry_time.into(),
+        );
+
+        let mut current_settings = current.settings.clone();
+        if let Some(settings) = current_settings.as_object_mut() {
+            settings.remove("clients");
+        }
+        let mut desired_settings = desired.settings.clone();
+        if let Some(settings) = desired_settings.as_object_mut() {
+            settings.remove("clients");
+        }
+        for (field, from, to) in [
+            ("settings", &current_settings, &desired_settings),
+            (
+                "streamSettings",
+                &current.stream_settings,
+                &desired.stream_settings,
+            ),
+            ("sniffing", &current.sniffing, &desired.sniffing),
+        ] {
+            if !to.is_null() {
+                diff_values(field, from, to, &mut field_changes);
+            }
+        }
+
+        if !field_changes.is_empty() {
+            updated.remark = desired.remark.clone();
+            updated.enable = desired.enable;
+            updated.protocol = desired.protocol.clone();
+            updated.total = desired.total;
+            updated.expiry_time = desired.expiry_time;
+            merge_values(&mut updated.settings, &desired_settings);
+            merge_values(&mut updated.stream_settings, &desired.stream_settings);
+            merge_values(&mut updated.sniffing, &desired.sniffing);
+            if self.prune && desired.protocol.has_clients() {
+                // Pruned clients are deleted before the update: sending them back would
+                // restore them
+                let kept: Vec<Client> = current
+                    .clients()?
+                    .into_iter()
+                    .filter(|client| {
+                        desired
+                            .clients
+                            .iter()
+                            .any(|w| w.client.email == client.email)
+                    })
+                    .collect();
+                updated.set_clients(&kept)?;
+                updated
+                    .client_stats
+                    .retain(|stats| kept.iter().any(|client| client.email == stats.email));
+            }
+
+            changes.push(Change::UpdateInbound {
+                id: current.id,
+                changes: field_changes,
+                inbound: updated,
+            });
+        }
+
+        if desired.protocol.has_clients() {
+            self.plan_clients(current, desired, changes)?;
+        }
+        Ok(())
+    }
+
+    /// Plans the client changes of an existing inbound.
+    fn plan_clients(
+        &self,
+        current: &Inbound,
+        desired: &DesiredInbound,
+        changes: &mut Vec<Change>,
+    ) -> Result<(), MyError> {
+        let current_clients = current.clients()?;
+
+        for wanted in &desired.clients {
+            match current_clients
+                .iter()
+                .find(|c| c.email == wanted.client.email)
+            {
+                Some(existing) => {
+                    let from = serde_json::to_value(existing)?;
+                    let to = Value::Object(wanted.fields.clone());
+                    let mut field_changes = Vec::new();
+                    diff_values("", &from, &to, &mut field_changes);
+                    if field_changes.is_empty() {
+                        continue;
+                    }
+
+                    let mut merged = from;
+                    merge_values(&mut merged, &to);
+                    changes.push(Change::UpdateClient {
+                        inbound_id: current.id,
+                        key: client_key(existing, &current.protocol),
+                        changes: field_changes,
+                        client: serde_json::from_value(merged)?,
+                    });
+                }
+                None => changes.push(Change::AddClient {
+                    inbound_id: current.id,
+                    client: with_credentials(&wanted.client, &desired.protocol),
+                }),
+            }
+        }
+
+        if self.prune {
+            for existing in current_clients
+                .iter()
+                .filter(|c| !desired.clients.iter().any(|w| w.client.email == c.email))
+            {
+                changes.push(Change::DeleteClient {
+                    inbound_id: current.id,
+                    key: client_key(existing, &current.protocol),
+                    email: existing.email.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the identifier used to address an existing client, falling back to its email.
+fn client_key(client: &Client, protocol: &Protocol) -> String {
+    client
+        .key(protocol)
+        .unwrap_or(client.email.as_str())
+        .to_string()
+}
+
+/// Collects the fields of `to` that differ from `from`, recursing into objects.
+///
+/// Keys present in `from` but absent from `to` are not reported: the desired state only
+/// manages the keys it declares.
+fn diff_values(path: &str, from: &Value, to: &Value, changes: &mut Vec<FieldChange>) {
+    match (from, to) {
+        (Value::Object(from), Value::Object(to)) => {
+            for (key, to_value) in to {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                diff_values(
+                    &child,
+                    from.get(key).unwrap_or(&Value::Null),
+                    to_value,
+                    changes,
+                );
+            }
+        }
+        (from, to) if from != to => changes.push(FieldChange {
+            field: path.to_string(),
+            from: from.clone(),
+            to: to.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Recursively merges the keys of `patch` into `target`; non-object values replace the
+/// target, and a `null` patch leaves it unchanged.
+fn merge_values(target: &mut Value, patch: &Value) {
+    match (target, patch) {
+        (_, Value::Null) => {}
+        (Value::Object(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge_values(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+        (target, patch) => *target = patch.clone(),
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::XUiClient;
+use crate::errors::MyError;
+use crate::models::{Inbound, default_tag, ensure_success};
+use crate::ops::now_millis;
+
+/// Format version written by [`XUiClient::export_snapshot`].
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A portable copy of all inbounds of a panel, with their clients and traffic counters.
+///
+/// Serialized as JSON; inbounds keep the panel's own shape (with `settings` and
+/// `streamSettings` as JSON-encoded strings), so a snapshot can also be inspected or
+/// edited by hand.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub version: u32,
+    /// Unix timestamp in milliseconds.
+    pub created_at: i64,
+    /// URL of the panel the snapshot was taken from.
+    #[serde(default)]
+    pub source: String,
+    pub inbounds: Vec<Inbound>,
+}
+
+impl Snapshot {
+    /// Serializes the snapshot as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, MyError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parses a snapshot, rejecting versions newer than this library understands.
+    pub fn from_json(json: &str) -> Result<Self, MyError> {
+        let snapshot: Snapshot = serde_json::from_str(json)?;
+        if snapshot.version > SNAPSHOT_VERSION {
+            return Err(MyError::CustomError(format!(
+                "Unsupported snapshot version {} (latest supported is {})",
+                snapshot.version, SNAPSHOT_VERSION
+            )));
+        }
+        Ok(snapshot)
+    }
+
+    /// Writes the snapshot to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), MyError> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Reads a snapshot from a file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MyError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// How [`XUiClient::import_snapshot`] adapts inbounds to the target panel.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportOptions {
+    ports: HashMap<u16, u16>,
+    listens: HashMap<String, String>,
+    tags: HashMap<String, String>,
+    keep_traffic: bool,
+}
+
+impl ImportOptions {
+    /// Creates options importing inbounds unchanged, with traffic counters reset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the inbound listening on port `from` to port `to`.
+    pub fn remap_port(mut self, from: u16, to: u16) -> Self {
+        self.ports.insert(from, to);
+        self
+    }
+
+    /// Replaces the listen address `from` (empty for all interfaces) with `to`.
+    pub fn remap_listen(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.listens.insert(from.into(), to.into());
+        self
+    }
+
+    /// Renames the inbound tag `from` to `to`.
+    ///
+    /// Tags that are not remapped but follow the panel's default `inbound-<port>` naming
+    /// are renamed along with the port and listen address.
+    pub fn remap_tag(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.tags.insert(from.into(), to.into());
+        self
+    }
+
+    /// When enabled, inbound and client traffic counters are carried over; otherwise
+    /// they start from zero.
+    pub fn keep_traffic(mut self, keep_traffic: bool) -> Self {
+        self.keep_traffic = keep_traffic;
+        self
+    }
+
+    /// Returns the inbound as it should be created on the target panel.
+    fn adapt(&self, inbound: &Inbound) -> Inbound {
+        let mut adapted = inbound.clone();
+        adapted.id = 0;
+
+        if let Some(port) = self.ports.get(&inbound.port) {
+            adapted.port = *port;
+        }
+        if let Some(listen) = self.listens.get(&inbound.listen) {
+            adapted.listen = listen.clone();
+        }
+        adapted.tag = match self.tags.get(&inbound.tag) {
+            Some(tag) => tag.clone(),
+            None if inbound.tag == default_tag(&inbound.listen, inbound.port) => {
+                default_tag(&adapted.listen, adapted.port)
+            }
+            None => inbound.tag.clone(),
+        };
+
+        for stats in &mut adapted.client_stats {
+            stats.id = 0;
+            stats.inbound_id = 0;
+            if !self.keep_traffic {
+                stats.up = 0;
+                stats.down = 0;
+            }
+        }
+        if !self.keep_traffic {
+            adapted.up = 0;
+            adapted.down = 0;
+        }
+
+        adapted
+    }
+}
+
+/// Outcome of importing one inbound.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportOutcome {
+    Created,
+    Failed(String),
+}
+
+/// Result of importing one inbound, identified by its remark and (remapped) port.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportItem {
+    pub remark: String,
+    pub port: u16,
+    pub outcome: ImportOutcome,
+}
+
+/// Per-inbound report of [`XUiClient::import_snapshot`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    pub items: Vec<ImportItem>,
+}
+
+impl ImportReport {
+    /// Returns the items that could not be created.
+    pub fn failed(&self) -> Vec<&ImportItem> {
+        self.items
+            .iter()
+            .filter(|item| matches!(item.outcome, ImportOutcome::Failed(_)))
+            .collect()
+    }
+
+    /// Returns `true` if every inbound was created.
+    pub fn is_success(&self) -> bool {
+        self.failed().is_empty()
+    }
+}
+
+impl XUiClient {
+    /// Reads all inbounds, clients and traffic counters of the panel into a [`Snapshot`].
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the snapshot if successful, or a `MyError` if the inbounds
+    /// could not be fetched or decoded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///
+    ///     let snapshot = client.export_snapshot().await?;
+    ///     snapshot.save("panel-snapshot.json")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn export_snapshot(&mut self) -> Result<Snapshot, MyError> {
+        let inbounds = self.get_inbounds_typed().await?;
+
+        Ok(Snapshot {
+            version: SNAPSHOT_VERSION,
+            created_at: now_millis(),
+            source: self.panel_base_url().to_string(),
+            inbounds,
+        })
+    }
+
+    /// Recreates the inbounds of a [`Snapshot`] on this panel with `add_inbound`.
+    ///
+    /// Inbounds are adapted according to `options` (ports, listen addresses, tags and
+    /// traffic counters). A failing inbound, e.g. because its port is already in use, does
+    /// not stop the import; it is reported in the returned [`ImportReport`].
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The snapshot to import, typically read with [`Snapshot::load`].
+    /// * `options` - How to adapt the inbounds to this panel.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    /// use xui_rs::snapshot::{ImportOptions, Snapshot};
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://new-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///
+    ///     let snapshot = Snapshot::load("panel-snapshot.json")?;
+    ///     let options = ImportOptions::new()
+    ///         .remap_port(443, 8443)
+    ///         .keep_traffic(true);
+    ///
+    ///     let report = client.import_snapshot(&snapshot, &options).await?;
+    ///     for item in report.failed() {
+    ///         println!("{} ({}): {:?}", item.remark, item.port, item.outcome);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn import_snapshot(
+        &mut self,
+        snapshot: &Snapshot,
+        options: &ImportOptions,
+    ) -> Result<ImportReport, MyError> {
+        let mut report = ImportReport::default();
+
+        for inbound in &snapshot.inbounds {
+            let adapted = options.adapt(inbound);
+
+            let outcome = match self.add_inbound(serde_json::to_value(&adapted)?).await {
+                Ok(response) => match ensure_success(&response) {
+                    Ok(()) => ImportOutcome::Created,
+                    Err(err) => ImportOutcome::Failed(err.to_string()),
+                },
+                Err(err) => ImportOutcome::Failed(err.to_string()),
+            };
+
+            report.items.push(ImportItem {
+                remark: adapted.remark,
+                port: adapted.port,
+                outcome,
+            });
+        }
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,245 @@
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use crate::errors::MyError;
+use crate::ops::MILLIS_PER_DAY;
+use crate::poller::TrafficSample;
+
+/// Traffic of a client over a period, in bytes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClientUsage {
+    pub email: String,
+    pub up: u64,
+    pub down: u64,
+}
+
+impl ClientUsage {
+    /// Returns the total traffic (upload + download).
+    pub fn total(&self) -> u64 {
+        self.up + self.down
+    }
+}
+
+/// Traffic of a client during one UTC day, in bytes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DailyUsage {
+    /// Start of the day (00:00 UTC), as a Unix timestamp in milliseconds.
+    pub day: i64,
+    pub email: String,
+    pub up: u64,
+    pub down: u64,
+}
+
+impl DailyUsage {
+    /// Returns the total traffic (upload + download).
+    pub fn total(&self) -> u64 {
+        self.up + self.down
+    }
+}
+
+/// History of the [`TrafficSample`]s produced by a
+/// [`TrafficPoller`](crate::poller::TrafficPoller), answering questions the panel's
+/// cumulative counters cannot, such as the usage of a client on a given day.
+///
+/// Implementations only store and return samples; the queries are provided on top of
+/// [`TrafficStore::samples`]. A sample belongs to the time range and the day its end
+/// (`to`) falls in, and clients are identified by email, which is unique per panel.
+/// Ranges are half-open: `from` is included, `to` is not.
+///
+/// The stores of this module leave out the clients and inbounds without traffic during
+/// the interval, which make most of a sample.
+///
+/// # Example
+///
+/// ```rust
+/// use xui_rs::api::XUiClient;
+/// use xui_rs::poller::TrafficPoller;
+/// use xui_rs::store::{JsonlStore, TrafficStore};
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let client = XUiClient::new("https://your-xui-panel.com/")?
+///         .with_credentials("admin", "password");
+///     let mut store = JsonlStore::new("traffic.jsonl");
+///
+///     let (mut samples, _task) = TrafficPoller::new(client).spawn(16);
+///     while let Some(sample) = samples.recv().await {
+///         store.record(&sample?)?;
+///     }
+///
+///     for usage in store.top_clients(10, 0, i64::MAX)? {
+///         println!("{}: {} bytes", usage.email, usage.total());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub trait TrafficStore {
+    /// Appends a sample to the history.
+    fn record(&mut self, sample: &TrafficSample) -> Result<(), MyError>;
+
+    /// Returns the samples ending within `[from, to)`, in recording order.
+    fn samples(&self, from: i64, to: i64) -> Result<Vec<TrafficSample>, MyError>;
+
+    /// Returns the traffic of every client with traffic within `[from, to)`, sorted by
+    /// email.
+    fn usage(&self, from: i64, to: i64) -> Result<Vec<ClientUsage>, MyError> {
+        let mut usage: BTreeMap<String, ClientUsage> = BTreeMap::new();
+        for sample in self.samples(from, to)? {
+            for delta in sample.clients {
+                let entry = usage
+                    .entry(delta.email.clone())
+                    .or_insert_with(|| ClientUsage {
+                        email: delta.email,
+                        ..Default::default()
+                    });
+                entry.up += delta.up;
+                entry.down += delta.down;
+            }
+        }
+        Ok(usage.into_values().collect())
+    }
+
+    /// Returns the traffic of one client within `[from, to)`.
+    fn client_usage(&self, email: &str, from: i64, to: i64) -> Result<ClientUsage, MyError> {
+        let usage = self
+            .usage(from, to)?
+            .into_iter()
+            .find(|usage| usage.email == email);
+        Ok(usage.unwrap_or_else(|| ClientUsage {
+            email: email.to_string(),
+            ..Default::default()
+        }))
+    }
+
+    /// Returns the traffic per client and per UTC day within `[from, to)`, sorted by day
+    /// then email.
+    fn daily_usage(&self, from: i64, to: i64) -> Result<Vec<DailyUsage>, MyError> {
+        let mut usage: BTreeMap<(i64, String), DailyUsage> = BTreeMap::new();
+        for sample in self.samples(from, to)? {
+            let day = sample.to.div_euclid(MILLIS_PER_DAY) * MILLIS_PER_DAY;
+            for delta in sample.clients {
+                let entry = usage
+                    .entry((day, delta.email.clone()))
+                    .or_insert_with(|| DailyUsage {
+                        day,
+                        email: delta.email,
+                        ..Default::default()
+                    });
+                entry.up += delta.up;
+                entry.down += delta.down;
+            }
+        }
+        Ok(usage.into_values().collect())
+    }
+
+    /// Returns the `n` clients with the most traffic within `[from, to)`, largest first.
+    fn top_clients(&self, n: usize, from: i64, to: i64) -> Result<Vec<ClientUsage>, MyError> {
+        let mut usage = self.usage(from, to)?;
+        usage.sort_by(|a, b| b.total().cmp(&a.total()).then(a.email.cmp(&b.email)));
+        usage.truncate(n);
+        Ok(usage)
+    }
+}
+
+/// Removes the clients and inbounds without traffic from a sample.
+fn compact(sample: &TrafficSample) -> TrafficSample {
+    let mut sample = sample.clone();
+    sample.clients.retain(|delta| delta.total() > 0);
+    sample.inbounds.retain(|delta| delta.total() > 0);
+    sample
+}
+
+/// A [`TrafficStore`] keeping samples in memory, e.g. for tests or short-lived processes.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    samples: Vec<TrafficSample>,
+}
+
+impl MemoryStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TrafficStore for MemoryStore {
+    fn record(&mut self, sample: &TrafficSample) -> Result<(), MyError> {
+        self.samples.push(compact(sample));
+        Ok(())
+    }
+
+    fn samples(&self, from: i64, to: i64) -> Result<Vec<TrafficSample>, MyError> {
+        Ok(self
+            .samples
+            .iter()
+            .filter(|sample| (from..to).contains(&sample.to))
+            .cloned()
+            .collect())
+    }
+}
+
+/// A [`TrafficStore`] appending samples to a file, one JSON object per line.
+///
+/// The file is created on the first sample and never rewritten, so it can be rotated or
+/// shipped with usual tools. Queries read the whole file.
+#[derive(Debug, Clone)]
+pub struct JsonlStore {
+    path: PathBuf,
+}
+
+impl JsonlStore {
+    /// Creates a store backed by the file at `path`, which may not exist yet.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns the path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl TrafficStore for JsonlStore {
+    fn record(&mut self, sample: &TrafficSample) -> Result<(), MyError> {
+        let mut line = serde_json::to_string(&compact(sample))?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    fn samples(&self, from: i64, to: i64) -> Result<Vec<TrafficSample>, MyError> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut samples = Vec::new();
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let sample: TrafficSample = serde_json::from_str(&line).map_err(|e| {
+                MyError::CustomError(format!(
+                    "Invalid sample at {}:{}: {}",
+                    self.path.display(),
+                    i + 1,
+                    e
+                ))
+            })?;
+            if (from..to).contains(&sample.to) {
+                samples.push(sample);
+            }
+        }
+        Ok(samples)
+    }
+}
@@ -0,0 +1,767 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use reqwest::IntoUrl;
+use reqwest::header::HeaderMap;
+use serde_json::{Map, Value, json};
+use std::time::Duration;
+
+use crate::api::XUiClient;
+use crate::errors::MyError;
+use crate::links::{ShareLink, decode_base64};
+use crate::models::{Client, ClientTraffic, Inbound, Protocol};
+
+/// Traffic and expiry summary sent in the `subscription-userinfo` header.
+///
+/// All traffic values are in bytes, `expire` is a Unix timestamp in seconds.
+/// `total` and `expire` are `0` when unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SubscriptionUserInfo {
+    pub upload: u64,
+    pub download: u64,
+    pub total: u64,
+    pub expire: u64,
+}
+
+impl std::fmt::Display for SubscriptionUserInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "upload={}; download={}; total={}; expire={}",
+            self.upload, self.download, self.total, self.expire
+        )
+    }
+}
+
+impl std::str::FromStr for SubscriptionUserInfo {
+    type Err = MyError;
+
+    /// Parses a `subscription-userinfo` header value such as
+    /// `upload=100; download=200; total=1000; expire=1767225600`.
+    ///
+    /// Unknown keys are ignored and missing keys default to `0`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut info = SubscriptionUserInfo::default();
+
+        for pair in value
+            .split(';')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+        {
+            let (key, raw) = pair.split_once('=').ok_or_else(|| {
+                MyError::CustomError(format!("Malformed subscription-userinfo entry '{}'", pair))
+            })?;
+            // Some panels send fractional values; only the integral part is meaningful.
+            let number: u64 = raw.trim().split('.').next().unwrap_or_default().parse()?;
+
+            match key.trim() {
+                "upload" => info.upload = number,
+                "download" => info.download = number,
+                "total" => info.total = number,
+                "expire" => info.expire = number,
+                _ => {}
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+/// A client of a subscription together with the inbound it belongs to.
+#[derive(Debug, Clone)]
+pub struct SubscriptionEntry {
+    /// Address clients use to reach the panel's inbounds.
+    pub address: String,
+    pub inbound: Inbound,
+    pub client: Client,
+    pub traffic: Option<ClientTraffic>,
+}
+
+/// Builds subscription content for a `subId` aggregated across any number of panels.
+///
+/// Entries are collected from typed inbounds (see `XUiClient::get_inbounds_typed`);
+/// only enabled clients of enabled inbounds whose `subId` matches are included,
+/// mirroring the panel's own subscription service.
+///
+/// # Example
+///
+/// ```rust
+/// use xui_rs::api::XUiClient;
+/// use xui_rs::subscription::SubscriptionBuilder;
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let mut panel_a = XUiClient::new("https://panel-a.example.com/")?;
+///     panel_a.login("admin", "password").await?;
+///     let mut panel_b = XUiClient::new("https://panel-b.example.com/")?;
+///     panel_b.login("admin", "password").await?;
+///
+///     let mut builder = SubscriptionBuilder::new("k8xq2nz7a1b2c3d4");
+///     builder.add_panel(&mut panel_a, "a.example.com").await?;
+///     builder.add_panel(&mut panel_b, "b.example.com").await?;
+///
+///     println!("subscription-userinfo: {}", builder.user_info());
+///     println!("{}", builder.to_base64()?);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SubscriptionBuilder {
+    sub_id: String,
+    entries: Vec<SubscriptionEntry>,
+}
+
+impl SubscriptionBuilder {
+    /// Creates an empty builder for the given `subId`.
+    pub fn new(sub_id: impl Into<String>) -> Self {
+        Self {
+            sub_id: sub_id.into(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the `subId` this builder collects clients for.
+    pub fn sub_id(&self) -> &str {
+        &self.sub_id
+    }
+
+    /// Returns the collected entries.
+    pub fn entries(&self) -> &[SubscriptionEntry] {
+        &self.entries
+    }
+
+    /// Adds the matching clients of `inbounds`, reachable at `address`.
+    pub fn add_inbounds(
+        &mut self,
+        address: impl Into<String>,
+        inbounds: &[Inbound],
+    ) -> Result<&mut Self, MyError> {
+        let address = address.into();
+
+        for inbound in inbounds.iter().filter(|inbound| inbound.enable) {
+            for client in inbound.clients()? {
+                if client.sub_id != self.sub_id || !client.enable {
+                    continue;
+                }
+
+                self.entries.push(SubscriptionEntry {
+                    address: address.clone(),
+                    traffic: inbound.client_traffic(&client.email).cloned(),
+                    inbound: inbound.clone(),
+                    client,
+                });
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Fetches the inbounds of a panel and adds its matching clients, reachable at `address`.
+    pub async fn add_panel(
+        &mut self,
+        client: &mut XUiClient,
+        address: impl Into<String>,
+    ) -> Result<&mut Self, MyError> {
+        let inbounds = client.get_inbounds_typed().await?;
+        self.add_inbounds(address, &inbounds)
+    }
+
+    /// Returns the share links of all collected entries.
+    pub fn links(&self) -> Result<Vec<ShareLink>, MyError> {
+        self.entries
+            .iter()
+            .map(|entry| ShareLink::from_inbound(&entry.inbound, &entry.client, &entry.address))
+            .collect()
+    }
+
+    /// Encodes the links as the classic base64, newline-separated subscription body.
+    pub fn to_base64(&self) -> Result<String, MyError> {
+        let uris = self
+            .links()?
+            .iter()
+            .map(ShareLink::to_uri)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(STANDARD.encode(uris.join("\n")))
+    }
+
+    /// Renders a Clash/Mihomo YAML profile with one proxy per link and a `PROXY` selector group.
+    ///
+    /// Links using a transport Clash cannot express (e.g. KCP) are skipped.
+    pub fn to_clash_yaml(&self) -> Result<String, MyError> {
+        let proxies: Vec<Value> = self.links()?.iter().filter_map(clash_proxy).collect();
+        let names: Vec<Value> = proxies.iter().map(|proxy| proxy["name"].clone()).collect();
+
+        let profile = json!({
+            "mixed-port": 7890,
+            "allow-lan": false,
+            "mode": "rule",
+            "log-level": "info",
+            "proxies": proxies,
+            "proxy-groups": [{
+                "name": "PROXY",
+                "type": "select",
+                "proxies": names,
+            }],
+            "rules": ["MATCH,PROXY"],
+        });
+
+        serde_yaml::to_string(&profile)
+            .map_err(|e| MyError::CustomError(format!("YAML serialization failed: {}", e)))
+    }
+
+    /// Renders a sing-box configuration fragment with one outbound per link,
+    /// a `proxy` selector and a `direct` outbound.
+    ///
+    /// Links using a transport sing-box cannot express (e.g. KCP) are skipped.
+    pub fn to_sing_box_json(&self) -> Result<Value, MyError> {
+        let mut outbounds: Vec<Value> =
+            self.links()?.iter().filter_map(sing_box_outbound).collect();
+        let tags: Vec<Value> = outbounds
+            .iter()
+            .map(|outbound| outbound["tag"].clone())
+            .collect();
+
+        outbounds.insert(
+            0,
+            json!({
+                "type": "selector",
+                "tag": "proxy",
+                "outbounds": tags,
+            }),
+        );
+        outbounds.push(json!({ "type": "direct", "tag": "direct" }));
+
+        Ok(json!({ "outbounds": outbounds }))
+    }
+
+    /// Renders one complete Xray client configuration per link, as served by the panel's
+    /// JSON subscription.
+    pub fn to_xray_json(&self) -> Result<Value, MyError> {
+        let configs: Vec<Value> = self
+            .links()?
+            .iter()
+            .map(|link| {
+                json!({
+                    "remarks": link.remark,
+                    "log": { "loglevel": "warning" },
+                    "inbounds": [
+                        {
+                            "tag": "socks",
+                            "listen": "127.0.0.1",
+                            "port": 10808,
+                            "protocol": "socks",
+                            "settings": { "udp": true },
+                            "sniffing": { "enabled": true, "destOverride": ["http", "tls"] },
+                        },
+                        {
+                            "tag": "http",
+                            "listen": "127.0.0.1",
+                            "port": 10809,
+                            "protocol": "http",
+                        },
+                    ],
+                    "outbounds": [
+                        xray_outbound(link),
+                        { "tag": "direct", "protocol": "freedom" },
+                        { "tag": "block", "protocol": "blackhole" },
+                    ],
+                    "routing": { "domainStrategy": "AsIs", "rules": [] },
+                })
+            })
+            .collect();
+
+        Ok(Value::Array(configs))
+    }
+
+    /// Computes the `subscription-userinfo` values from the collected clients' traffic.
+    ///
+    /// Traffic is summed over all entries; the quota is unlimited if any entry is
+    /// unlimited, and the expiry is the earliest fixed expiry, or unlimited if any entry
+    /// never expires or has not started yet ("start on first use").
+    pub fn user_info(&self) -> SubscriptionUserInfo {
+        let mut info = SubscriptionUserInfo::default();
+        let mut unlimited_total = false;
+        let mut unlimited_expiry = false;
+        let mut earliest_expiry: Option<i64> = None;
+
+        for entry in &self.entries {
+            let (up, down, total, expiry_time) = match &entry.traffic {
+                Some(traffic) => (traffic.up, traffic.down, traffic.total, traffic.expiry_time),
+                None => (0, 0, entry.client.total_gb, entry.client.expiry_time),
+            };
+
+            info.upload += up;
+            info.download += down;
+
+            if total == 0 {
+                unlimited_total = true;
+            } else {
+                info.total += total;
+            }
+
+            if expiry_time <= 0 {
+                unlimited_expiry = true;
+            } else {
+                earliest_expiry = Some(earliest_expiry.map_or(expiry_time, |e| e.min(expiry_time)));
+            }
+        }
+
+        if unlimited_total {
+            info.total = 0;
+        }
+        if !unlimited_expiry {
+            info.expire = earliest_expiry.map_or(0, |ms| (ms / 1000) as u64);
+        }
+
+        info
+    }
+}
+
+fn clash_proxy(link: &ShareLink) -> Option<Value> {
+    let stream = &link.stream;
+    let mut proxy = Map::new();
+    proxy.insert("name".into(), json!(link.remark));
+    proxy.insert("server".into(), json!(link.address));
+    proxy.insert("port".into(), json!(link.port));
+    proxy.insert("udp".into(), json!(true));
+
+    match link.protocol {
+        Protocol::Vless => {
+            proxy.insert("type".into(), json!("vless"));
+            proxy.insert("uuid".into(), json!(link.credential));
+            if !link.flow.is_empty() {
+                proxy.insert("flow".into(), json!(link.flow));
+            }
+        }
+        Protocol::Vmess => {
+            proxy.insert("type".into(), json!("vmess"));
+            proxy.insert("uuid".into(), json!(link.credential));
+            proxy.insert("alterId".into(), json!(0));
+            proxy.insert("cipher".into(), json!("auto"));
+        }
+        Protocol::Trojan => {
+            proxy.insert("type".into(), json!("trojan"));
+            proxy.insert("password".into(), json!(link.credential));
+        }
+        Protocol::Shadowsocks => {
+            proxy.insert("type".into(), json!("ss"));
+            proxy.insert("cipher".into(), json!(link.method));
+            proxy.insert("password".into(), json!(link.credential));
+            return Some(Value::Object(proxy));
+        }
+        _ => return None,
+    }
+
+    match stream.network.as_str() {
+        "tcp" => {
+            proxy.insert("network".into(), json!("tcp"));
+        }
+        "ws" => {
+            proxy.insert("network".into(), json!("ws"));
+            let mut opts = json!({ "path": stream.path });
+            if !stream.host.is_empty() {
+                opts["headers"] = json!({ "Host": stream.host });
+            }
+            proxy.insert("ws-opts".into(), opts);
+        }
+        "grpc" => {
+            proxy.insert("network".into(), json!("grpc"));
+            proxy.insert(
+                "grpc-opts".into(),
+                json!({ "grpc-service-name": stream.service_name }),
+            );
+        }
+        "httpupgrade" => {
+            proxy.insert("network".into(), json!("ws"));
+            let mut opts = json!({ "path": stream.path, "v2ray-http-upgrade": true });
+            if !stream.host.is_empty() {
+                opts["headers"] = json!({ "Host": stream.host });
+            }
+            proxy.insert("ws-opts".into(), opts);
+        }
+        _ => return None,
+    }
+
+    match stream.security.as_str() {
+        "tls" | "reality" => {
+            proxy.insert("tls".into(), json!(true));
+            if !stream.sni.is_empty() {
+                let key = if link.protocol == Protocol::Trojan {
+                    "sni"
+                } else {
+                    "servername"
+                };
+                proxy.insert(key.into(), json!(stream.sni));
+            }
+            if !stream.fingerprint.is_empty() {
+                proxy.insert("client-fingerprint".into(), json!(stream.fingerprint));
+            }
+            if !stream.alpn.is_empty() {
+                proxy.insert("alpn".into(), json!(stream.alpn));
+            }
+            if stream.security == "reality" {
+                proxy.insert(
+                    "reality-opts".into(),
+                    json!({ "public-key": stream.public_key, "short-id": stream.short_id }),
+                );
+            }
+        }
+        _ => {
+            proxy.insert("tls".into(), json!(false));
+        }
+    }
+
+    Some(Value::Object(proxy))
+}
+
+fn sing_box_outbound(link: &ShareLink) -> Option<Value> {
+    let stream = &link.stream;
+    let mut outbound = Map::new();
+    outbound.insert("tag".into(), json!(link.remark));
+    outbound.insert("server".into(), json!(link.address));
+    outbound.insert("server_port".into(), json!(link.port));
+
+    match link.protocol {
+        Protocol::Vless => {
+            outbound.insert("type".into(), json!("vless"));
+            outbound.insert("uuid".into(), json!(link.credential));
+            if !link.flow.is_empty() {
+                outbound.insert("flow".into(), json!(link.flow));
+            }
+        }
+        Protocol::Vmess => {
+            outbound.insert("type".into(), json!("vmess"));
+            outbound.insert("uuid".into(), json!(link.credential));
+            outbound.insert("security".into(), json!("auto"));
+            outbound.insert("alter_id".into(), json!(0));
+        }
+        Protocol::Trojan => {
+            outbound.insert("type".into(), json!("trojan"));
+            outbound.insert("password".into(), json!(link.credential));
+        }
+        Protocol::Shadowsocks => {
+            outbound.insert("type".into(), json!("shadowsocks"));
+            outbound.insert("method".into(), json!(link.method));
+            outbound.insert("password".into(), json!(link.credential));
+            return Some(Value::Object(outbound));
+        }
+        _ => return None,
+    }
+
+    match stream.network.as_str() {
+        "tcp" => {}
+        "ws" => {
+            let mut transport = json!({ "type": "ws", "path": stream.path });
+            if !stream.host.is_empty() {
+                transport["headers"] = json!({ "Host": stream.host });
+            }
+            outbound.insert("transport".into(), transport);
+        }
+        "grpc" => {
+            outbound.insert(
+                "transport".into(),
+                json!({ "type": "grpc", "service_name": stream.service_name }),
+            );
+        }
+        "httpupgrade" => {
+            outbound.insert(
+                "transport".into(),
+                json!({ "type": "httpupgrade", "path": stream.path, "host": stream.host }),
+            );
+        }
+        _ => return None,
+    }
+
+    if stream.security == "tls" || stream.security == "reality" {
+        let mut tls = json!({ "enabled": true, "server_name": stream.sni });
+        if !stream.alpn.is_empty() {
+            tls["alpn"] = json!(stream.alpn);
+        }
+        if !stream.fingerprint.is_empty() {
+            tls["utls"] = json!({ "enabled": true, "fingerprint": stream.fingerprint });
+        }
+        if stream.security == "reality" {
+            tls["reality"] = json!({
+                "enabled": true,
+                "public_key": stream.public_key,
+                "short_id": stream.short_id,
+            });
+        }
+        outbound.insert("tls".into(), tls);
+    }
+
+    Some(Value::Object(outbound))
+}
+
+fn xray_outbound(link: &ShareLink) -> Value {
+    let stream = &link.stream;
+
+    let settings = match link.protocol {
+        Protocol::Vless => json!({
+            "vnext": [{
+                "address": link.address,
+                "port": link.port,
+                "users": [{ "id": link.credential, "encryption": "none", "flow": link.flow }],
+            }],
+        }),
+        Protocol::Vmess => json!({
+            "vnext": [{
+                "address": link.address,
+                "port": link.port,
+                "users": [{ "id": link.credential, "alterId": 0, "security": "auto" }],
+            }],
+        }),
+        Protocol::Shadowsocks => json!({
+            "servers": [{
+                "address": link.address,
+                "port": link.port,
+                "method": link.method,
+                "password": link.credential,
+            }],
+        }),
+        _ => json!({
+            "servers": [{
+                "address": link.address,
+                "port": link.port,
+                "password": link.credential,
+            }],
+        }),
+    };
+
+    let mut stream_settings = json!({
+        "network": stream.network,
+        "security": stream.security,
+    });
+    match stream.network.as_str() {
+        "tcp" if stream.header_type == "http" => {
+            stream_settings["tcpSettings"] = json!({
+                "header": {
+                    "type": "http",
+                    "request": { "path": [stream.path], "headers": { "Host": [stream.host] } },
+                },
+            });
+        }
+        "ws" => {
+            stream_settings["wsSettings"] = json!({ "path": stream.path, "host": stream.host });
+        }
+        "grpc" => {
+            stream_settings["grpcSettings"] = json!({
+                "serviceName": stream.service_name,
+                "multiMode": stream.mode == "multi",
+            });
+        }
+        "httpupgrade" => {
+            stream_settings["httpupgradeSettings"] =
+                json!({ "path": stream.path, "host": stream.host });
+        }
+        "xhttp" | "splithttp" => {
+            stream_settings["xhttpSettings"] =
+                json!({ "path": stream.path, "host": stream.host, "mode": stream.mode });
+        }
+        "kcp" => {
+            stream_settings["kcpSettings"] =
+                json!({ "header": { "type": stream.header_type }, "seed": stream.seed });
+        }
+        _ => {}
+    }
+    match stream.security.as_str() {
+        "tls" => {
+            stream_settings["tlsSettings"] = json!({
+                "serverName": stream.sni,
+                "fingerprint": stream.fingerprint,
+                "alpn": stream.alpn,
+            });
+        }
+        "reality" => {
+            stream_settings["realitySettings"] = json!({
+                "serverName": stream.sni,
+                "fingerprint": stream.fingerprint,
+                "publicKey": stream.public_key,
+                "shortId": stream.short_id,
+                "spiderX": stream.spider_x,
+            });
+        }
+        _ => {}
+    }
+
+    json!({
+        "tag": "proxy",
+        "protocol": link.protocol.as_str(),
+        "settings": settings,
+        "streamSettings": stream_settings,
+    })
+}
+
+/// Metadata sent by a subscription server alongside the subscription body.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SubscriptionHeaders {
+    /// Parsed `subscription-userinfo` header.
+    pub user_info: Option<SubscriptionUserInfo>,
+    /// Parsed `profile-update-interval` header (sent in hours).
+    pub update_interval: Option<Duration>,
+    /// Parsed `profile-title` header, with `base64:` titles decoded.
+    pub title: Option<String>,
+}
+
+impl SubscriptionHeaders {
+    /// Extracts the subscription metadata from response headers.
+    pub fn from_headers(headers: &HeaderMap) -> Result<Self, MyError> {
+        let user_info = match headers.get("subscription-userinfo") {
+            Some(value) => Some(value.to_str()?.parse()?),
+            None => None,
+        };
+
+        let update_interval = match headers.get("profile-update-interval") {
+            Some(value) => {
+                let hours: u64 = value.to_str()?.trim().parse()?;
+                Some(Duration::from_secs(hours * 3600))
+            }
+            None => None,
+        };
+
+        let title = match headers.get("profile-title") {
+            Some(value) => {
+                let raw = value.to_str()?;
+                match raw.strip_prefix("base64:") {
+                    Some(encoded) => {
+                        decode_base64(encoded).and_then(|bytes| String::from_utf8(bytes).ok())
+                    }
+                    None => Some(raw.to_string()),
+                }
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            user_info,
+            update_interval,
+            title,
+        })
+    }
+}
+
+/// A link subscription as received by end-user apps.
+#[derive(Debug, Clone)]
+pub struct FetchedSubscription {
+    pub headers: SubscriptionHeaders,
+    /// The decoded share links, one per line of the body.
+    pub uris: Vec<String>,
+    /// The parsed share links, in the same order as `uris`.
+    pub links: Vec<ShareLink>,
+}
+
+/// A JSON subscription (full Xray client configurations) as received by end-user apps.
+#[derive(Debug, Clone)]
+pub struct FetchedJsonSubscription {
+    pub headers: SubscriptionHeaders,
+    pub configs: Vec<Value>,
+}
+
+/// Fetches subscriptions the way end-user apps do, e.g. `https://host:2096/sub/{subId}`.
+///
+/// Unlike `XUiClient`, no panel credentials are needed: subscription URLs are public.
+///
+/// # Example
+///
+/// ```rust
+/// use xui_rs::subscription::SubscriptionClient;
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let client = SubscriptionClient::new()?;
+///     let subscription = client.fetch("https://your-xui-panel.com:2096/sub/k8xq2nz7a1b2c3d4").await?;
+///     for link in &subscription.links {
+///         println!("{} {}:{}", link.remark, link.address, link.port);
+///     }
+///     println!("{:?}", subscription.headers.user_info);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SubscriptionClient {
+    client: reqwest::Client,
+}
+
+impl SubscriptionClient {
+    /// Creates a new subscription client with its own HTTP client.
+    pub fn new() -> Result<Self, MyError> {
+        let client = match reqwest::Client::builder().build() {
+            Ok(client) => client,
+            Err(e) => return Err(MyError::ReqwestError(e)),
+        };
+
+        Ok(Self { client })
+    }
+
+    /// Creates a subscription client reusing an existing HTTP client.
+    pub fn with_client(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+
+    /// Sends a GET request and returns the parsed headers and the raw body.
+    async fn get(&self, url: impl IntoUrl) -> Result<(SubscriptionHeaders, String), MyError> {
+        let response = self.client.get(url).send().await?;
+
+        if !response.status().is_success() {
+            return Err(MyError::CustomError(format!(
+                "Subscription request failed with status: {}",
+                response.status()
+            )));
+        }
+
+        let headers = SubscriptionHeaders::from_headers(response.headers())?;
+        let body = response.text().await?;
+
+        Ok((headers, body))
+    }
+
+    /// Fetches a link subscription, decodes its (optionally base64-encoded) body and
+    /// parses every share link.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `MyError` if the request fails, the server answers with a non-success
+    /// status or one of the links cannot be parsed.
+    pub async fn fetch(&self, url: impl IntoUrl) -> Result<FetchedSubscription, MyError> {
+        let (headers, body) = self.get(url).await?;
+
+        let text = if body.contains("://") {
+            body
+        } else {
+            decode_base64(&body)
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+                .ok_or_else(|| {
+                    MyError::CustomError("Subscription body is not valid base64".to_string())
+                })?
+        };
+
+        let uris: Vec<String> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        let links = uris
+            .iter()
+            .map(|uri| uri.parse())
+            .collect::<Result<Vec<ShareLink>, _>>()?;
+
+        Ok(FetchedSubscription {
+            headers,
+            uris,
+            links,
+        })
+    }
+
+    /// Fetches a JSON subscription (e.g. `https://host:2096/json/{subId}`).
+    ///
+    /// A single configuration object is returned as a one-element list.
+    pub async fn fetch_json(&self, url: impl IntoUrl) -> Result<FetchedJsonSubscription, MyError> {
+        let (headers, body) = self.get(url).await?;
+
+        let configs = match serde_json::from_str(&body)? {
+            Value::Array(configs) => configs,
+            config => vec![config],
+        };
+
+        Ok(FetchedJsonSubscription { headers, configs })
+    }
+}
@@ -0,0 +1,399 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::api::XUiClient;
+use crate::errors::MyError;
+use crate::models::{Inbound, Protocol};
+
+/// Transports (`streamSettings.network`) supported by Xray.
+const NETWORKS: &[&str] = &[
+    "tcp",
+    "raw",
+    "kcp",
+    "ws",
+    "http",
+    "grpc",
+    "httpupgrade",
+    "xhttp",
+    "splithttp",
+];
+
+/// Transports REALITY can be used with.
+const REALITY_NETWORKS: &[&str] = &["tcp", "raw", "grpc", "xhttp"];
+
+/// Valid values of a VLESS client's `flow`.
+const FLOWS: &[&str] = &["xtls-rprx-vision", "xtls-rprx-vision-udp443"];
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The panel would reject the inbound or Xray would fail to start with it.
+    Error,
+    /// The inbound works but is probably not what was intended.
+    Warning,
+}
+
+/// A problem found in an inbound, attached to the field it concerns.
+///
+/// `field` is a dotted path such as `streamSettings.realitySettings.dest` or
+/// `settings.clients[2].email`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}: {}: {}", severity, self.field, self.message)
+    }
+}
+
+/// Diagnostics found by [`validate_inbound`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ValidationReport {
+    /// Returns the diagnostics of severity [`Severity::Error`].
+    pub fn errors(&self) -> Vec<&Diagnostic> {
+        self.with_severity(Severity::Error)
+    }
+
+    /// Returns the diagnostics of severity [`Severity::Warning`].
+    pub fn warnings(&self) -> Vec<&Diagnostic> {
+        self.with_severity(Severity::Warning)
+    }
+
+    /// Returns `true` if no error was found; warnings are allowed.
+    pub fn is_valid(&self) -> bool {
+        self.errors().is_empty()
+    }
+
+    fn with_severity(&self, severity: Severity) -> Vec<&Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .collect()
+    }
+
+    fn error(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Error, field, message);
+    }
+
+    fn warning(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Warning, field, message);
+    }
+
+    fn push(&mut self, severity: Severity, field: impl Into<String>, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            field: field.into(),
+            message: message.into(),
+        });
+    }
+}
+
+/// Checks an inbound before it is sent with `add_inbound` or `update_inbound`.
+///
+/// `existing` are the inbounds already on the panel (the inbound itself, matched by ID,
+/// is ignored, so an inbound being updated can be validated against the full list).
+///
+/// The checks cover:
+/// - the port, and conflicts with existing inbounds listening on the same address;
+/// - the transport and security layer, and their compatibility with the protocol
+///   (REALITY requires VLESS over tcp, grpc or xhttp);
+/// - the fields required by REALITY (`dest`, `serverNames`, `privateKey`) and TLS
+///   (certificate and key);
+/// - clients: credentials, `flow`, and emails that must be unique across the panel.
+///
+/// # Example
+///
+/// ```rust
+/// use xui_rs::models::Inbound;
+/// use xui_rs::validate::validate_inbound;
+///
+/// fn example(inbound: &Inbound, existing: &[Inbound]) {
+///     let report = validate_inbound(inbound, existing);
+///     for diagnostic in &report.diagnostics {
+///         println!("{}", diagnostic);
+///     }
+/// }
+/// ```
+pub fn validate_inbound(inbound: &Inbound, existing: &[Inbound]) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let others: Vec<&Inbound> = existing
+        .iter()
+        .filter(|other| inbound.id == 0 || other.id != inbound.id)
+        .collect();
+
+    check_port(inbound, &others, &mut report);
+    if uses_stream_settings(&inbound.protocol) {
+        check_stream(inbound, &mut report);
+    }
+    check_clients(inbound, &others, &mut report);
+
+    report
+}
+
+impl XUiClient {
+    /// Validates an inbound against the inbounds currently on the panel, see
+    /// [`validate_inbound`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    /// use xui_rs::models::Inbound;
+    ///
+    /// async fn example(inbound: Inbound) -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///
+    ///     let report = client.validate_inbound(&inbound).await?;
+    ///     if report.is_valid() {
+    ///         client.add_inbound(serde_json::to_value(&inbound)?).await?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn validate_inbound(
+        &mut self,
+        inbound: &Inbound,
+    ) -> Result<ValidationReport, MyError> {
+        let existing = self.get_inbounds_typed().await?;
+        Ok(validate_inbound(inbound, &existing))
+    }
+}
+
+/// Returns `true` for protocols carried over Xray's stream settings.
+fn uses_stream_settings(protocol: &Protocol) -> bool {
+    matches!(
+        protocol,
+        Protocol::Vmess | Protocol::Vless | Protocol::Trojan | Protocol::Shadowsocks
+    )
+}
+
+/// Returns `true` if two listen addresses overlap, an empty address meaning all interfaces.
+fn listens_overlap(a: &str, b: &str) -> bool {
+    let any = |listen: &str| listen.is_empty() || listen == "0.0.0.0" || listen == "::";
+    any(a) || any(b) || a == b
+}
+
+fn check_port(inbound: &Inbound, others: &[&Inbound], report: &mut ValidationReport) {
+    if inbound.port == 0 {
+        report.error("port", "port must be between 1 and 65535");
+        return;
+    }
+
+    for other in others {
+        if other.port == inbound.port && listens_overlap(&other.listen, &inbound.listen) {
+            report.error(
+                "port",
+                format!(
+                    "port {} is already used by inbound {} \"{}\"",
+                    inbound.port, other.id, other.remark
+                ),
+            );
+        }
+    }
+}
+
+fn check_stream(inbound: &Inbound, report: &mut ValidationReport) {
+    let network = inbound.network();
+    if !NETWORKS.contains(&network) {
+        report.error(
+            "streamSettings.network",
+            format!("unknown transport \"{}\"", network),
+        );
+    }
+
+    match inbound.security() {
+        "none" => {}
+        "tls" => check_tls(&inbound.stream_settings["tlsSettings"], report),
+        "reality" => {
+            if inbound.protocol != Protocol::Vless {
+                report.error(
+                    "streamSettings.security",
+                    format!(
+                        "REALITY is only supported with VLESS, not {}",
+                        inbound.protocol
+                    ),
+                );
+            }
+            if !REALITY_NETWORKS.contains(&network) {
+                report.error(
+                    "streamSettings.security",
+                    format!(
+                        "REALITY is only supported over tcp, grpc or xhttp, not {}",
+                        network
+                    ),
+                );
+            }
+            check_reality(&inbound.stream_settings["realitySettings"], report);
+        }
+        other => report.error(
+            "streamSettings.security",
+            format!("unknown security \"{}\"", other),
+        ),
+    }
+}
+
+fn check_tls(tls: &Value, report: &mut ValidationReport) {
+    let certificates = tls["certificates"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    if certificates.is_empty() {
+        report.error(
+            "streamSettings.tlsSettings.certificates",
+            "no certificate configured",
+        );
+    }
+
+    for (i, certificate) in certificates.iter().enumerate() {
+        let has = |key: &str| match &certificate[key] {
+            Value::String(s) => !s.is_empty(),
+            Value::Array(lines) => !lines.is_empty(),
+            _ => false,
+        };
+        let from_files = has("certificateFile") && has("keyFile");
+        let inline = has("certificate") && has("key");
+        if !from_files && !inline {
+            report.error(
+                format!("streamSettings.tlsSettings.certificates[{}]", i),
+                "certificateFile and keyFile (or certificate and key) are required",
+            );
+        }
+    }
+
+    if tls["serverName"].as_str().unwrap_or_default().is_empty() {
+        report.warning("streamSettings.tlsSettings.serverName", "no SNI configured");
+    }
+}
+
+fn check_reality(reality: &Value, report: &mut ValidationReport) {
+    let dest = reality["dest"]
+        .as_str()
+        .or_else(|| reality["target"].as_str())
+        .unwrap_or_default();
+    if dest.is_empty() {
+        report.error("streamSettings.realitySettings.dest", "dest is required");
+    } else if !dest.contains(':') {
+        report.warning(
+            "streamSettings.realitySettings.dest",
+            format!("dest \"{}\" has no port, e.g. {}:443", dest, dest),
+        );
+    }
+
+    let has_server_names = reality["serverNames"].as_array().is_some_and(|names| {
+        names
+            .iter()
+            .any(|name| name.as_str().is_some_and(|s| !s.is_empty()))
+    });
+    if !has_server_names {
+        report.error(
+            "streamSettings.realitySettings.serverNames",
+            "at least one server name is required",
+        );
+    }
+
+    if reality["privateKey"]
+        .as_str()
+        .unwrap_or_default()
+        .is_empty()
+    {
+        report.error(
+            "streamSettings.realitySettings.privateKey",
+            "privateKey is required",
+        );
+    }
+
+    if reality["shortIds"]
+        .as_array()
+        .is_none_or(|ids| ids.is_empty())
+    {
+        report.warning(
+            "streamSettings.realitySettings.shortIds",
+            "no short ID configured, clients cannot connect",
+        );
+    }
+}
+
+fn check_clients(inbound: &Inbound, others: &[&Inbound], report: &mut ValidationReport) {
+    if !inbound.protocol.has_clients() {
+        return;
+    }
+
+    let clients = match inbound.clients() {
+        Ok(clients) => clients,
+        Err(err) => {
+            report.error("settings.clients", format!("invalid clients: {}", err));
+            return;
+        }
+    };
+
+    let mut taken: HashSet<String> = HashSet::new();
+    for other in others {
+        for client in other.clients().unwrap_or_default() {
+            taken.insert(client.email);
+        }
+    }
+
+    let flow_allowed = inbound.protocol == Protocol::Vless
+        && matches!(inbound.network(), "tcp" | "raw")
+        && matches!(inbound.security(), "tls" | "reality");
+
+    let mut seen = HashSet::new();
+    for (i, client) in clients.iter().enumerate() {
+        let field = |name: &str| format!("settings.clients[{}].{}", i, name);
+
+        if client.email.is_empty() {
+            report.error(field("email"), "email is required");
+        } else if !seen.insert(client.email.as_str()) {
+            report.error(
+                field("email"),
+                format!("duplicate email \"{}\" in this inbound", client.email),
+            );
+        } else if taken.contains(&client.email) {
+            report.error(
+                field("email"),
+                format!(
+                    "email \"{}\" is already used by another inbound",
+                    client.email
+                ),
+            );
+        }
+
+        match inbound.protocol {
+            Protocol::Vmess | Protocol::Vless => match client.id.as_deref() {
+                Some(id) if uuid::Uuid::parse_str(id).is_ok() => {}
+                Some(id) => report.error(field("id"), format!("\"{}\" is not a valid UUID", id)),
+                None => report.error(field("id"), "UUID is required"),
+            },
+            Protocol::Trojan if client.password.as_deref().unwrap_or_default().is_empty() => {
+                report.error(field("password"), "password is required");
+            }
+            _ => {}
+        }
+
+        if !client.flow.is_empty() {
+            if !FLOWS.contains(&client.flow.as_str()) {
+                report.error(field("flow"), format!("unknown flow \"{}\"", client.flow));
+            } else if !flow_allowed {
+                report.error(
+                    field("flow"),
+                    "flow requires VLESS over tcp with TLS or REALITY",
+                );
+            }
+        }
+    }
+}
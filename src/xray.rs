@@ -0,0 +1,507 @@
+use std::path::Path;
+
+use serde_json::{Map, Value, json};
+
+use crate::api::XUiClient;
+use crate::errors::MyError;
+use crate::keys;
+use crate::models::{Client, Inbound, Protocol, default_tag, ensure_success};
+use crate::snapshot::{ImportItem, ImportOutcome, ImportReport};
+
+/// Default port of the local API inbound, the one used by the panel.
+pub const DEFAULT_API_PORT: u16 = 62789;
+
+/// Generates a standalone Xray `config.json` from typed inbounds, so the inbounds pushed
+/// to 3X-UI can also drive nodes running bare Xray.
+///
+/// The configuration mirrors the one the panel generates: one Xray inbound per enabled
+/// panel inbound (disabled clients left out), `stats`, `api` and `policy` sections for
+/// per-client traffic counters, an API inbound on `127.0.0.1`, and `direct`/`blocked`
+/// outbounds with a routing rule for the API.
+///
+/// # Example
+///
+/// ```rust
+/// use xui_rs::api::XUiClient;
+/// use xui_rs::xray::XrayConfigBuilder;
+///
+/// async fn example() -> Result<(), xui_rs::errors::MyError> {
+///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+///     client.login("admin", "password").await?;
+///
+///     let inbounds = client.get_inbounds_typed().await?;
+///     let config = XrayConfigBuilder::new().log_level("info").build(&inbounds)?;
+///     std::fs::write("config.json", serde_json::to_string_pretty(&config)?)?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct XrayConfigBuilder {
+    log_level: String,
+    api_port: Option<u16>,
+    outbounds: Option<Vec<Value>>,
+}
+
+impl Default for XrayConfigBuilder {
+    fn default() -> Self {
+        Self {
+            log_level: "warning".to_string(),
+            api_port: Some(DEFAULT_API_PORT),
+            outbounds: None,
+        }
+    }
+}
+
+impl XrayConfigBuilder {
+    /// Creates a builder with the panel's defaults: `warning` logs and the API on
+    /// [`DEFAULT_API_PORT`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the Xray log level (`debug`, `info`, `warning`, `error` or `none`).
+    pub fn log_level(mut self, log_level: impl Into<String>) -> Self {
+        self.log_level = log_level.into();
+        self
+    }
+
+    /// Sets the port of the local API inbound, or disables the API, stats and policy
+    /// sections with `None`.
+    pub fn api_port(mut self, api_port: Option<u16>) -> Self {
+        self.api_port = api_port;
+        self
+    }
+
+    /// Replaces the default `direct` (freedom) and `blocked` (blackhole) outbounds.
+    pub fn outbounds(mut self, outbounds: Vec<Value>) -> Self {
+        self.outbounds = Some(outbounds);
+        self
+    }
+
+    /// Renders the configuration for the given inbounds; disabled inbounds are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `MyError` if the clients of an inbound cannot be decoded.
+    pub fn build(&self, inbounds: &[Inbound]) -> Result<Value, MyError> {
+        let mut xray_inbounds = Vec::new();
+        let mut rules = Vec::new();
+
+        if let Some(port) = self.api_port {
+            xray_inbounds.push(json!({
+                "tag": "api",
+                "listen": "127.0.0.1",
+                "port": port,
+                "protocol": "dokodemo-door",
+                "settings": {"address": "127.0.0.1"}
+            }));
+            rules.push(json!({"type": "field", "inboundTag": ["api"], "outboundTag": "api"}));
+        }
+
+        for inbound in inbounds.iter().filter(|inbound| inbound.enable) {
+            xray_inbounds.push(xray_inbound(inbound)?);
+        }
+
+        let outbounds = self.outbounds.clone().unwrap_or_else(|| {
+            vec![
+                json!({"tag": "direct", "protocol": "freedom", "settings": {}}),
+                json!({"tag": "blocked", "protocol": "blackhole", "settings": {}}),
+            ]
+        });
+
+        let mut config = json!({
+            "log": {"loglevel": self.log_level, "access": "none"},
+            "inbounds": xray_inbounds,
+            "outbounds": outbounds,
+            "routing": {"domainStrategy": "AsIs", "rules": rules},
+        });
+
+        if self.api_port.is_some() {
+            config["api"] = json!({
+                "tag": "api",
+                "services": ["HandlerService", "LoggerService", "StatsService"]
+            });
+            config["stats"] = json!({});
+            config["policy"] = json!({
+                "levels": {"0": {"statsUserUplink": true, "statsUserDownlink": true}},
+                "system": {
+                    "statsInboundUplink": true,
+                    "statsInboundDownlink": true,
+                    "statsOutboundUplink": true,
+                    "statsOutboundDownlink": true
+                }
+            });
+        }
+
+        Ok(config)
+    }
+}
+
+/// Converts a panel inbound into an Xray inbound object.
+///
+/// Panel-only fields (`externalProxy`, the client-side `settings` of REALITY and TLS,
+/// client quotas and expiry, WireGuard peer private keys) are removed, and only enabled
+/// clients are kept, with the fields Xray understands for the protocol.
+pub fn xray_inbound(inbound: &Inbound) -> Result<Value, MyError> {
+    let mut settings = match &inbound.settings {
+        Value::Object(settings) => settings.clone(),
+        _ => Map::new(),
+    };
+
+    if inbound.protocol.has_clients() {
+        let clients: Vec<Value> = inbound
+            .clients()?
+            .iter()
+            .filter(|client| {
+                client.enable
+                    && inbound
+                        .client_traffic(&client.email)
+                        .is_none_or(|traffic| traffic.enable)
+            })
+            .map(|client| xray_client(client, &inbound.protocol))
+            .collect();
+        settings.insert("clients".to_string(), Value::Array(clients));
+    }
+
+    if inbound.protocol == Protocol::Wireguard
+        && let Some(peers) = settings.get_mut("peers").and_then(Value::as_array_mut)
+    {
+        for peer in peers.iter_mut().filter_map(Value::as_object_mut) {
+            peer.remove("privateKey");
+        }
+    }
+
+    let mut xray = json!({
+        "tag": inbound.tag,
+        "listen": if inbound.listen.is_empty() { Value::Null } else { json!(inbound.listen) },
+        "port": inbound.port,
+        "protocol": inbound.protocol.as_str(),
+        "settings": settings,
+    });
+
+    if let Value::Object(stream) = &inbound.stream_settings {
+        let mut stream = stream.clone();
+        stream.remove("externalProxy");
+        for security in ["realitySettings", "tlsSettings"] {
+            if let Some(Value::Object(section)) = stream.get_mut(security) {
+                section.remove("settings");
+            }
+        }
+        xray["streamSettings"] = Value::Object(stream);
+    }
+    if inbound.sniffing.is_object() {
+        xray["sniffing"] = inbound.sniffing.clone();
+    }
+
+    Ok(xray)
+}
+
+/// Keeps the client fields Xray uses for the protocol.
+fn xray_client(client: &Client, protocol: &Protocol) -> Value {
+    let mut xray = Map::new();
+    xray.insert("email".to_string(), json!(client.email));
+
+    match protocol {
+        Protocol::Vmess | Protocol::Vless => {
+            xray.insert(
+                "id".to_string(),
+                json!(client.id.clone().unwrap_or_default()),
+            );
+        }
+        _ => {
+            xray.insert(
+                "password".to_string(),
+                json!(client.password.clone().unwrap_or_default()),
+            );
+        }
+    }
+    if *protocol == Protocol::Vless && !client.flow.is_empty() {
+        xray.insert("flow".to_string(), json!(client.flow));
+    }
+    if let Some(method) = client.method.as_deref().filter(|method| !method.is_empty()) {
+        xray.insert("method".to_string(), json!(method));
+    }
+
+    Value::Object(xray)
+}
+
+/// Inbounds read from an Xray `config.json` by [`XrayImport::from_config`], in the shape
+/// 3X-UI expects.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XrayImport {
+    pub inbounds: Vec<Inbound>,
+    /// Inbounds that could not be converted, with the reason.
+    pub skipped: Vec<SkippedInbound>,
+}
+
+/// An inbound of the Xray configuration left out of an [`XrayImport`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedInbound {
+    /// Tag of the inbound, or its position in `inbounds` if it has none.
+    pub tag: String,
+    pub reason: String,
+}
+
+impl XrayImport {
+    /// Converts the inbounds of a parsed Xray configuration.
+    ///
+    /// VLESS, VMess, Trojan, Shadowsocks, SOCKS, HTTP, dokodemo-door (or `tunnel`) and
+    /// WireGuard inbounds are converted with their stream settings and sniffing; other
+    /// protocols, port ranges and the local API inbound are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `MyError` if the configuration has no `inbounds` array.
+    pub fn from_config(config: &Value) -> Result<Self, MyError> {
+        let inbounds = config
+            .get("inbounds")
+            .and_then(Value::as_array)
+            .ok_or_else(|| {
+                MyError::CustomError("Xray config has no \"inbounds\" array".to_string())
+            })?;
+
+        let mut import = XrayImport::default();
+        for (i, xray) in inbounds.iter().enumerate() {
+            match inbound_from_xray(xray) {
+                Ok(inbound) => import.inbounds.push(inbound),
+                Err(err) => import.skipped.push(SkippedInbound {
+                    tag: xray["tag"]
+                        .as_str()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| format!("inbounds[{}]", i)),
+                    reason: match err {
+                        MyError::CustomError(reason) => reason,
+                        other => other.to_string(),
+                    },
+                }),
+            }
+        }
+
+        Ok(import)
+    }
+
+    /// Parses an Xray configuration and converts its inbounds.
+    pub fn from_json(json: &str) -> Result<Self, MyError> {
+        Self::from_config(&serde_json::from_str(json)?)
+    }
+
+    /// Reads an Xray `config.json` and converts its inbounds.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MyError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
+
+impl XUiClient {
+    /// Creates the inbounds of an [`XrayImport`] on this panel with `add_inbound`.
+    ///
+    /// A failing inbound, e.g. because its port is already in use, does not stop the
+    /// import; it is reported in the returned [`ImportReport`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use xui_rs::api::XUiClient;
+    /// use xui_rs::xray::XrayImport;
+    ///
+    /// async fn example() -> Result<(), xui_rs::errors::MyError> {
+    ///     let mut client = XUiClient::new("https://your-xui-panel.com/")?;
+    ///     client.login("admin", "password").await?;
+    ///
+    ///     let import = XrayImport::load("/usr/local/etc/xray/config.json")?;
+    ///     for skipped in &import.skipped {
+    ///         println!("Skipped {}: {}", skipped.tag, skipped.reason);
+    ///     }
+    ///
+    ///     let report = client.import_xray_config(&import).await?;
+    ///     println!("Imported: {}", report.is_success());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn import_xray_config(
+        &mut self,
+        import: &XrayImport,
+    ) -> Result<ImportReport, MyError> {
+        let mut report = ImportReport::default();
+
+        for inbound in &import.inbounds {
+            let outcome = match self.add_inbound(serde_json::to_value(inbound)?).await {
+                Ok(response) => match ensure_success(&response) {
+                    Ok(()) => ImportOutcome::Created,
+                    Err(err) => ImportOutcome::Failed(err.to_string()),
+                },
+                Err(err) => ImportOutcome::Failed(err.to_string()),
+            };
+
+            report.items.push(ImportItem {
+                remark: inbound.remark.clone(),
+                port: inbound.port,
+                outcome,
+            });
+        }
+
+        Ok(report)
+    }
+}
+
+/// Converts an Xray inbound object into a panel inbound, the reverse of [`xray_inbound`].
+///
+/// Clients without an email get a random one (emails identify clients in the panel), and
+/// missing credentials are generated. For REALITY and TLS, the client-side `settings`
+/// used by the panel for share links are filled in (the REALITY public key is derived
+/// from the private key).
+///
+/// # Errors
+///
+/// Returns a `MyError::CustomError` for unsupported protocols, port ranges, the local API
+/// inbound, Shadowsocks without `method` and REALITY without a valid `privateKey`.
+pub fn inbound_from_xray(xray: &Value) -> Result<Inbound, MyError> {
+    let protocol = match xray["protocol"].as_str().unwrap_or_default() {
+        "tunnel" => Protocol::DokodemoDoor,
+        name => Protocol::from(name.to_string()),
+    };
+    if let Protocol::Other(name) = &protocol {
+        return Err(MyError::CustomError(format!(
+            "unsupported protocol \"{}\"",
+            name
+        )));
+    }
+
+    let tag = xray["tag"].as_str().unwrap_or_default();
+    if tag == "api" && protocol == Protocol::DokodemoDoor {
+        return Err(MyError::CustomError("local API inbound".to_string()));
+    }
+
+    let port = match &xray["port"] {
+        Value::Number(port) => port.as_u64().and_then(|port| u16::try_from(port).ok()),
+        Value::String(port) => port.parse().ok(),
+        _ => None,
+    }
+    .filter(|port| *port != 0)
+    .ok_or_else(|| MyError::CustomError(format!("unsupported port {}", xray["port"])))?;
+
+    let listen = xray["listen"].as_str().unwrap_or_default().to_string();
+    let mut settings = match &xray["settings"] {
+        Value::Object(settings) => settings.clone(),
+        _ => Map::new(),
+    };
+
+    if protocol.has_clients() {
+        let clients = panel_clients(&protocol, &mut settings)?;
+        settings.insert("clients".to_string(), serde_json::to_value(clients)?);
+    }
+    if protocol == Protocol::Vless && !settings.contains_key("decryption") {
+        settings.insert("decryption".to_string(), json!("none"));
+    }
+
+    let stream_settings = match &xray["streamSettings"] {
+        Value::Object(stream) => panel_stream_settings(stream.clone())?,
+        _ if protocol.has_clients() => json!({"network": "tcp", "security": "none"}),
+        _ => Value::Null,
+    };
+    let sniffing = match &xray["sniffing"] {
+        Value::Object(sniffing) => Value::Object(sniffing.clone()),
+        _ => json!({"enabled": false, "destOverride": ["http", "tls", "quic", "fakedns"]}),
+    };
+
+    Ok(Inbound {
+        remark: if tag.is_empty() {
+            format!("{}-{}", protocol, port)
+        } else {
+            tag.to_string()
+        },
+        enable: true,
+        tag: if tag.is_empty() {
+            default_tag(&listen, port)
+        } else {
+            tag.to_string()
+        },
+        listen,
+        port,
+        protocol,
+        settings: Value::Object(settings),
+        stream_settings,
+        sniffing,
+        ..Default::default()
+    })
+}
+
+/// Builds the panel clients of an Xray inbound, turning a single-user Shadowsocks
+/// configuration into one client.
+fn panel_clients(
+    protocol: &Protocol,
+    settings: &mut Map<String, Value>,
+) -> Result<Vec<Client>, MyError> {
+    let mut clients: Vec<Client> = match settings.get("clients") {
+        Some(Value::Array(clients)) => serde_json::from_value(Value::Array(clients.clone()))?,
+        _ => Vec::new(),
+    };
+
+    if *protocol == Protocol::Shadowsocks && clients.is_empty() {
+        let method = settings
+            .get("method")
+            .and_then(Value::as_str)
+            .ok_or_else(|| MyError::CustomError("Shadowsocks without method".to_string()))?
+            .to_string();
+        if !method.starts_with("2022-")
+            && let Some(Value::String(password)) = settings.remove("password")
+        {
+            clients.push(Client {
+                password: Some(password),
+                method: Some(method),
+                ..Default::default()
+            });
+        }
+    }
+
+    for client in &mut clients {
+        if client.email.is_empty() {
+            client.email = keys::short_id(8);
+        }
+        if client.sub_id.is_empty() {
+            client.sub_id = keys::short_id(16);
+        }
+        client.generate_credentials(protocol);
+    }
+
+    Ok(clients)
+}
+
+/// Adds the client-side `settings` the panel keeps next to the REALITY and TLS settings.
+fn panel_stream_settings(mut stream: Map<String, Value>) -> Result<Value, MyError> {
+    if let Some(Value::Object(reality)) = stream.get_mut("realitySettings")
+        && !reality.contains_key("settings")
+    {
+        let public_key = reality
+            .get("privateKey")
+            .and_then(Value::as_str)
+            .and_then(|private_key| keys::reality_public_key(private_key).ok())
+            .ok_or_else(|| {
+                MyError::CustomError("REALITY without a valid privateKey".to_string())
+            })?;
+        let server_name = reality
+            .get("serverNames")
+            .and_then(|names| names.get(0))
+            .cloned()
+            .unwrap_or(json!(""));
+        reality.insert(
+            "settings".to_string(),
+            json!({
+                "publicKey": public_key,
+                "fingerprint": "chrome",
+                "serverName": server_name,
+                "spiderX": "/"
+            }),
+        );
+    }
+
+    if let Some(Value::Object(tls)) = stream.get_mut("tlsSettings")
+        && !tls.contains_key("settings")
+    {
+        tls.insert(
+            "settings".to_string(),
+            json!({"allowInsecure": false, "fingerprint": "chrome"}),
+        );
+    }
+
+    Ok(Value::Object(stream))
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use common::*;
+use httpmock::prelude::*;
+use serde_json::json;
+use xui_rs::alerts::{AlertEngine, AlertRule, CallbackSink, WebhookSink};
+use xui_rs::models::ClientTraffic;
+
+const DAY: i64 = 24 * 60 * 60 * 1000;
+const NOW: i64 = 100 * DAY;
+
+fn traffic(
+    email: &str,
+    used: u64,
+    total: u64,
+    expiry_time: i64,
+    last_online: i64,
+) -> ClientTraffic {
+    ClientTraffic {
+        inbound_id: 1,
+        enable: true,
+        email: email.to_string(),
+        down: used,
+        total,
+        expiry_time,
+        last_online,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_alert_rules_and_deduplication() {
+    let mut engine = AlertEngine::new()
+        .rule(AlertRule::QuotaUsed { percent: 80 })
+        .rule(AlertRule::QuotaUsed { percent: 95 })
+        .rule(AlertRule::ExpiresWithin { days: 3 })
+        .rule(AlertRule::InactiveFor { days: 30 });
+
+    let stats = vec![
+        traffic("alice", 85, 100, 0, NOW),
+        traffic("bob", 10, 0, NOW + 2 * DAY, NOW),
+        traffic("carol", 0, 100, -DAY, NOW - 31 * DAY),
+        // Never online and no quota: nothing to report
+        traffic("dave", 1000, 0, 0, 0),
+    ];
+    let alerts = engine.evaluate(&stats, NOW);
+    let raised: Vec<(&str, AlertRule)> = alerts
+        .iter()
+        .map(|alert| (alert.email.as_str(), alert.rule))
+        .collect();
+    assert_eq!(
+        raised,
+        [
+            ("alice", AlertRule::QuotaUsed { percent: 80 }),
+            ("bob", AlertRule::ExpiresWithin { days: 3 }),
+            ("carol", AlertRule::InactiveFor { days: 30 }),
+        ]
+    );
+    assert_eq!(
+        alerts[0].message,
+        "alice used 85% of its quota (85 of 100 bytes)"
+    );
+
+    // Already raised alerts are not repeated, a new threshold is
+    let mut stats = stats;
+    stats[0] = traffic("alice", 96, 100, 0, NOW);
+    let alerts = engine.evaluate(&stats, NOW + 1);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].rule, AlertRule::QuotaUsed { percent: 95 });
+
+    // A quota increase re-arms the alerts
+    stats[0] = traffic("alice", 96, 1000, 0, NOW);
+    assert!(engine.evaluate(&stats, NOW + 2).is_empty());
+    stats[0] = traffic("alice", 960, 1000, 0, NOW);
+    assert_eq!(engine.evaluate(&stats, NOW + 3).len(), 2);
+}
+
+#[test]
+fn test_alert_deduplication_with_partial_traffic() {
+    let mut engine = AlertEngine::new().rule(AlertRule::QuotaUsed { percent: 80 });
+    let first = [traffic("alice", 85, 100, 0, NOW)];
+    let mut second = [traffic("bob", 90, 100, 0, NOW)];
+    second[0].inbound_id = 2;
+
+    // Two disjoint slices, e.g. one inbound at a time
+    assert_eq!(engine.evaluate(&first, NOW).len(), 1);
+    assert_eq!(engine.evaluate(&second, NOW).len(), 1);
+
+    // Neither slice re-arms the clients of the other
+    assert!(engine.evaluate(&first, NOW + 1).is_empty());
+    let all = [first[0].clone(), second[0].clone()];
+    assert!(engine.evaluate(&all, NOW + 2).is_empty());
+
+    // A client that stopped matching is re-armed when evaluated
+    assert!(
+        engine
+            .evaluate(&[traffic("alice", 10, 100, 0, NOW)], NOW + 3)
+            .is_empty()
+    );
+    assert_eq!(engine.evaluate(&all, NOW + 4).len(), 1);
+}
+
+#[tokio::test]
+async fn test_alert_sinks() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    let mut inbounds = inbounds_json();
+    inbounds[0]["clientStats"][1]["total"] = json!(80);
+    mock_inbounds(&server, inbounds);
+    let webhook_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/hooks/alerts")
+            .json_body_partial(r#"{"email": "bob", "rule": {"type": "quotaUsed", "percent": 80}}"#);
+        then.status(204);
+    });
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink_received = received.clone();
+    let mut engine = AlertEngine::new()
+        .rule(AlertRule::QuotaUsed { percent: 80 })
+        .sink(CallbackSink::new(move |alert| {
+            sink_received.lock().unwrap().push(alert.email.clone())
+        }))
+        .sink(WebhookSink::new(server.url("/hooks/alerts")));
+
+    let alerts = engine.check(&mut client).await.unwrap();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(*received.lock().unwrap(), ["bob"]);
+    webhook_mock.assert();
+
+    // Deduplicated on the next poll
+    assert!(engine.check(&mut client).await.unwrap().is_empty());
+    webhook_mock.assert_hits(1);
+}
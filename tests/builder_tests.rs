@@ -0,0 +1,163 @@
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use xui_rs::builder::InboundBuilder;
+use xui_rs::keys;
+use xui_rs::links::ShareLink;
+use xui_rs::models::{Client, Protocol};
+use xui_rs::validate::validate_inbound;
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_x25519_keys() {
+    // RFC 7748 section 6.1 test vector
+    let private = hex("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+    let public = hex("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a");
+
+    assert_eq!(
+        keys::wireguard_public_key(&STANDARD.encode(&private)).unwrap(),
+        STANDARD.encode(&public)
+    );
+    assert_eq!(
+        keys::reality_public_key(&URL_SAFE_NO_PAD.encode(&private)).unwrap(),
+        URL_SAFE_NO_PAD.encode(&public)
+    );
+    assert!(keys::reality_public_key("too-short").is_err());
+
+    let pair = keys::reality_keypair();
+    assert_eq!(
+        keys::reality_public_key(&pair.private_key).unwrap(),
+        pair.public_key
+    );
+    assert_eq!(keys::short_id(8).len(), 8);
+    assert_eq!(
+        STANDARD
+            .decode(keys::shadowsocks_2022_key("2022-blake3-aes-128-gcm"))
+            .unwrap()
+            .len(),
+        16
+    );
+}
+
+#[test]
+fn test_vless_reality_builder() {
+    let inbound = InboundBuilder::vless_reality(443)
+        .remark("reality")
+        .dest("www.example.com:443")
+        .server_names(["www.example.com"])
+        .build()
+        .unwrap();
+
+    assert_eq!(inbound.protocol, Protocol::Vless);
+    assert_eq!(inbound.tag, "inbound-443");
+    let reality = &inbound.stream_settings["realitySettings"];
+    assert_eq!(
+        keys::reality_public_key(reality["privateKey"].as_str().unwrap()).unwrap(),
+        reality["settings"]["publicKey"]
+    );
+    assert_eq!(reality["shortIds"].as_array().unwrap().len(), 2);
+
+    // One default client with a generated UUID and the Vision flow
+    let clients = inbound.clients().unwrap();
+    assert_eq!(clients.len(), 1);
+    assert_eq!(clients[0].flow, "xtls-rprx-vision");
+    assert!(clients[0].id.is_some());
+
+    let report = validate_inbound(&inbound, &[]);
+    assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+
+    let link = ShareLink::from_inbound(&inbound, &clients[0], "vpn.example.com")
+        .unwrap()
+        .to_uri()
+        .unwrap();
+    assert!(link.contains("sni=www.example.com"));
+}
+
+#[test]
+fn test_tls_builders() {
+    let vmess = InboundBuilder::vmess_ws_tls(8443)
+        .listen("10.0.0.1")
+        .path("/ws")
+        .host("cdn.example.com")
+        .server_name("cdn.example.com")
+        .certificate("/etc/ssl/cert.pem", "/etc/ssl/key.pem")
+        .clients([
+            Client {
+                email: "a".to_string(),
+                ..Default::default()
+            },
+            Client {
+                email: "b".to_string(),
+                ..Default::default()
+            },
+        ])
+        .build()
+        .unwrap();
+    assert_eq!(vmess.tag, "inbound-10.0.0.1:8443");
+    assert_eq!(vmess.network(), "ws");
+    assert_eq!(vmess.clients().unwrap().len(), 2);
+    assert!(validate_inbound(&vmess, &[]).diagnostics.is_empty());
+
+    let trojan = InboundBuilder::trojan_grpc_tls(2083)
+        .service_name("svc")
+        .server_name("vpn.example.com")
+        .certificate("/etc/ssl/cert.pem", "/etc/ssl/key.pem")
+        .build()
+        .unwrap();
+    assert_eq!(trojan.stream_settings["grpcSettings"]["serviceName"], "svc");
+    assert!(trojan.clients().unwrap()[0].password.is_some());
+    assert!(validate_inbound(&trojan, &[]).is_valid());
+
+    // Without a certificate the validator complains
+    let missing = InboundBuilder::vmess_ws_tls(8444).build().unwrap();
+    assert!(!validate_inbound(&missing, &[]).is_valid());
+}
+
+#[test]
+fn test_other_builders() {
+    let ss = InboundBuilder::shadowsocks_2022(8388)
+        .method("2022-blake3-aes-128-gcm")
+        .build()
+        .unwrap();
+    assert_eq!(ss.settings["method"], "2022-blake3-aes-128-gcm");
+    let server_key = STANDARD
+        .decode(ss.settings["password"].as_str().unwrap())
+        .unwrap();
+    assert_eq!(server_key.len(), 16);
+    let client_key = ss.clients().unwrap()[0].password.clone().unwrap();
+    assert_eq!(STANDARD.decode(client_key).unwrap().len(), 16);
+
+    let socks = InboundBuilder::socks(1080)
+        .account("user", "pass")
+        .udp(true)
+        .build()
+        .unwrap();
+    assert_eq!(socks.settings["auth"], "password");
+    assert_eq!(socks.settings["accounts"][0]["user"], "user");
+    assert!(socks.stream_settings.is_null());
+
+    let http = InboundBuilder::http(3128).build().unwrap();
+    assert_eq!(http.protocol, Protocol::Http);
+
+    let forward = InboundBuilder::dokodemo_door(5353)
+        .target("1.1.1.1", 53)
+        .build()
+        .unwrap();
+    assert_eq!(forward.settings["address"], "1.1.1.1");
+    assert_eq!(forward.settings["port"], 53);
+
+    let wireguard = InboundBuilder::wireguard(51820).build().unwrap();
+    let peers = wireguard.settings["peers"].as_array().unwrap();
+    assert_eq!(peers.len(), 1);
+    assert_eq!(peers[0]["allowedIPs"][0], "10.0.0.2/32");
+    assert_eq!(wireguard.sniffing["enabled"], false);
+
+    // Encoded the way add_inbound expects it
+    let encoded = serde_json::to_value(&wireguard).unwrap();
+    assert!(encoded["settings"].is_string());
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use common::*;
+use httpmock::prelude::*;
+use serde_json::json;
+use xui_rs::bulk::{BulkAction, BulkOperation, BulkOutcome};
+use xui_rs::clients::ClientPatch;
+
+#[tokio::test]
+async fn test_bulk_dry_run() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    let list_mock = mock_inbounds(&server, inbounds_json());
+    let delete_mock = server.mock(|when, then| {
+        when.method(POST).path_contains("/delClient/");
+        then.status(200).json_body(ok_response());
+    });
+
+    let report = BulkOperation::new(|_, c| c.sub_id == "sub-1", BulkAction::Delete)
+        .dry_run(true)
+        .run(&mut client)
+        .await
+        .unwrap();
+
+    assert!(report.dry_run);
+    let emails: Vec<&str> = report.items.iter().map(|i| i.email.as_str()).collect();
+    assert_eq!(emails, ["alice", "carol"]);
+    assert!(
+        report
+            .items
+            .iter()
+            .all(|i| i.outcome == BulkOutcome::Planned)
+    );
+
+    list_mock.assert();
+    delete_mock.assert_hits(0);
+}
+
+#[tokio::test]
+async fn test_bulk_delete_with_failure() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    mock_inbounds(&server, inbounds_json());
+    let alice_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/1/delClient/aaaaaaaa-0000-0000-0000-000000000001/");
+        then.status(200).json_body(ok_response());
+    });
+    let carol_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/2/delClient/carol-secret/");
+        then.status(200)
+            .json_body(json!({"success": false, "msg": "Something went wrong", "obj": null}));
+    });
+
+    let report = BulkOperation::new(|_, c| c.sub_id == "sub-1", BulkAction::Delete)
+        .concurrency(2)
+        .run(&mut client)
+        .await
+        .unwrap();
+
+    assert!(!report.dry_run);
+    assert_eq!(report.items.len(), 2);
+    assert_eq!(report.succeeded().len(), 1);
+    assert_eq!(report.succeeded()[0].email, "alice");
+    assert_eq!(report.failed().len(), 1);
+    assert_eq!(report.failed()[0].inbound_id, 2);
+
+    alice_mock.assert();
+    carol_mock.assert();
+}
+
+#[tokio::test]
+async fn test_bulk_patch() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    mock_inbounds(&server, inbounds_json());
+    let update_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path_contains("/panel/api/inbounds/updateClient/")
+            .body_contains(r#"\"limitIp\":3"#);
+        then.status(200).json_body(ok_response());
+    });
+
+    let patch = ClientPatch {
+        limit_ip: Some(3),
+        ..Default::default()
+    };
+    let report = BulkOperation::new(|inbound, _| inbound.id == 1, BulkAction::Patch(patch))
+        .run(&mut client)
+        .await
+        .unwrap();
+
+    assert_eq!(report.succeeded().len(), 2);
+    update_mock.assert_hits(2);
+}
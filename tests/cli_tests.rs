@@ -0,0 +1,444 @@
+#![cfg(feature = "cli")]
+
+mod common;
+
+use clap::Parser;
+use common::*;
+use httpmock::prelude::*;
+use serde_json::{Value, json};
+use xui_rs::cli::{Cli, PanelArgs, format_bytes, format_expiry, run, select_profile};
+use xui_rs::fleet::FleetConfig;
+
+fn cli(server: &MockServer, args: &[&str]) -> Cli {
+    let url = server.url("/");
+    let mut argv = vec![
+        "xui",
+        "--url",
+        &url,
+        "--username",
+        "user",
+        "--password",
+        "pass",
+    ];
+    argv.extend_from_slice(args);
+    Cli::try_parse_from(argv).unwrap()
+}
+
+// Writes `content` to a file of the temporary directory and returns its path
+fn temp_file(name: &str, content: &str) -> String {
+    let path = std::env::temp_dir().join(format!("xui-rs-cli-{}-{}", std::process::id(), name));
+    std::fs::write(&path, content).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[test]
+fn test_select_profile_and_formatting() {
+    let config = FleetConfig::from_yaml(
+        r#"
+panels:
+  - name: default
+    url: https://default.example.com/
+  - name: de-1
+    url: https://de-1.example.com/
+    username: admin
+    password: secret
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(select_profile(&config, None).unwrap().name, "default");
+    assert_eq!(
+        select_profile(&config, Some("de-1")).unwrap().password,
+        "secret"
+    );
+    assert!(select_profile(&config, Some("nl-1")).is_err());
+    let single = FleetConfig {
+        panels: config.panels[1..].to_vec(),
+    };
+    assert_eq!(select_profile(&single, None).unwrap().name, "de-1");
+
+    assert_eq!(format_bytes(512), "512 B");
+    assert_eq!(format_bytes(1536 * 1024 * 1024), "1.50 GiB");
+    assert_eq!(format_expiry(0, 1_000), "never");
+    assert_eq!(format_expiry(500, 1_000), "expired");
+    assert_eq!(format_expiry(1_000 + 90_000_000, 1_000), "in 1d 1h 0m");
+    assert_eq!(
+        format_expiry(-86_400_000, 1_000),
+        "1d 0h 0m after first use"
+    );
+}
+
+#[tokio::test]
+async fn test_inbounds_list_outputs() {
+    let server = setup_mock_server();
+    logged_in_client(&server).await;
+    mock_inbounds(&server, inbounds_json());
+
+    let table = run(&cli(&server, &["inbounds", "list"])).await.unwrap();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("ID  REMARK  PROTOCOL  PORT"));
+    assert!(lines[1].starts_with("1   vmess   vmess     10001"));
+    assert!(lines[2].contains("trojan"));
+
+    let output = run(&cli(&server, &["-o", "json", "inbounds", "list"]))
+        .await
+        .unwrap();
+    let inbounds: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(inbounds[1]["port"], 10002);
+
+    let output = run(&cli(&server, &["inbounds", "list", "--output", "yaml"]))
+        .await
+        .unwrap();
+    assert!(output.contains("remark: trojan"));
+}
+
+#[tokio::test]
+async fn test_clients_add_generates_credentials() {
+    let server = setup_mock_server();
+    logged_in_client(&server).await;
+    mock_inbound(&server, 2);
+    let add_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/addClient/")
+            .matches(|req| {
+                let body: Value = serde_json::from_slice(req.body.as_deref().unwrap()).unwrap();
+                let settings: Value =
+                    serde_json::from_str(body["settings"].as_str().unwrap()).unwrap();
+                let client = &settings["clients"][0];
+                body["id"] == 2
+                    && client["email"] == "dave"
+                    && client["password"].as_str().is_some_and(|p| !p.is_empty())
+                    && client["totalGB"] == 5 * 1024 * 1024 * 1024_u64
+                    && client["limitIp"] == 2
+            });
+        then.status(200).json_body(ok_response());
+    });
+
+    let output = run(&cli(
+        &server,
+        &[
+            "clients",
+            "add",
+            "2",
+            "dave",
+            "--quota-gb",
+            "5",
+            "--limit-ip",
+            "2",
+        ],
+    ))
+    .await
+    .unwrap();
+
+    add_mock.assert();
+    assert_eq!(output, "Added client dave to inbound 2\n");
+    assert_eq!(
+        run(&cli(&server, &["clients", "update", "2", "dave"]))
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Custom error: Nothing to update: give at least one field"
+    );
+}
+
+#[tokio::test]
+async fn test_traffic_onlines_and_ips() {
+    let server = setup_mock_server();
+    logged_in_client(&server).await;
+    server.mock(|when, then| {
+        when.method(GET)
+            .path("/panel/api/inbounds/getClientTraffics/alice/");
+        then.status(200).json_body(json!({
+            "success": true,
+            "msg": "",
+            "obj": {"id": 1, "inboundId": 1, "enable": true, "email": "alice", "up": 1024, "down": 2048, "expiryTime": 0, "total": 0, "reset": 0}
+        }));
+    });
+    server.mock(|when, then| {
+        when.method(GET)
+            .path("/panel/api/inbounds/getClientTraffics/ghost/");
+        then.status(200)
+            .json_body(json!({"success": true, "msg": "", "obj": null}));
+    });
+    server.mock(|when, then| {
+        when.method(POST).path("/panel/api/inbounds/onlines/");
+        then.status(200)
+            .json_body(json!({"success": true, "msg": "", "obj": ["alice", "carol"]}));
+    });
+    server.mock(|when, then| {
+        when.method(POST).path("/panel/api/inbounds/clientIps/bob/");
+        then.status(200).json_body(json!({
+            "success": true,
+            "msg": "",
+            "obj": "[\"1.1.1.1\", \"2.2.2.2\"]"
+        }));
+    });
+    let clear_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/clearClientIps/bob/");
+        then.status(200)
+            .json_body(json!({"success": true, "msg": "Log Cleared", "obj": null}));
+    });
+
+    let table = run(&cli(&server, &["traffic", "alice"])).await.unwrap();
+    let lines: Vec<&str> = table.lines().collect();
+    assert!(lines[0].starts_with("EMAIL  INBOUND  ENABLED  UP"));
+    assert!(lines[1].starts_with("alice  1        true     1.00 KiB  2.00 KiB"));
+    assert_eq!(
+        run(&cli(&server, &["traffic", "ghost"]))
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Custom error: Client 'ghost' not found"
+    );
+
+    let output = run(&cli(&server, &["-o", "json", "onlines"]))
+        .await
+        .unwrap();
+    let online: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(online, json!(["alice", "carol"]));
+
+    let table = run(&cli(&server, &["ips", "bob"])).await.unwrap();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with("1.1.1.1"));
+    let output = run(&cli(&server, &["ips", "bob", "--clear"]))
+        .await
+        .unwrap();
+    assert_eq!(output, "Log Cleared\n");
+    clear_mock.assert();
+}
+
+#[tokio::test]
+async fn test_backup_and_status() {
+    let server = setup_mock_server();
+    logged_in_client(&server).await;
+    let mut backup_mock = server.mock(|when, then| {
+        when.method(GET).path("/panel/api/inbounds/createbackup/");
+        then.status(200);
+    });
+    server.mock(|when, then| {
+        when.method(POST).path("/server/status/");
+        then.status(200).json_body(json!({
+            "success": true,
+            "msg": "",
+            "obj": {
+                "cpu": 12.5,
+                "cpuCores": 4,
+                "mem": {"current": 1024, "total": 4096},
+                "xray": {"state": "running", "errorMsg": "", "version": "25.1.1"},
+                "uptime": 3600,
+                "loads": [0.1, 0.2, 0.3],
+                "tcpCount": 7,
+                "udpCount": 3,
+                "netIO": {"up": 10, "down": 20},
+                "publicIP": {"ipv4": "1.2.3.4", "ipv6": ""}
+            }
+        }));
+    });
+
+    let output = run(&cli(&server, &["backup"])).await.unwrap();
+    assert_eq!(output, "Backup requested\n");
+    backup_mock.assert();
+    backup_mock.delete();
+    server.mock(|when, then| {
+        when.method(GET).path("/panel/api/inbounds/createbackup/");
+        then.status(500);
+    });
+    assert_eq!(
+        run(&cli(&server, &["backup"]))
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Custom error: Backup failed with status: 500"
+    );
+
+    let output = run(&cli(&server, &["status"])).await.unwrap();
+    assert!(output.contains("CPU:         12.5% of 4 cores\n"));
+    assert!(output.contains("Xray:        running (25.1.1)\n"));
+    assert!(output.contains("Connections: 7 TCP, 3 UDP\n"));
+    assert!(output.contains("Public IP:   1.2.3.4\n"));
+
+    let output = run(&cli(&server, &["-o", "yaml", "status"])).await.unwrap();
+    assert!(output.contains("cpuCores: 4"));
+}
+
+#[tokio::test]
+async fn test_clients_delete_find_reset() {
+    let server = setup_mock_server();
+    logged_in_client(&server).await;
+    mock_inbounds(&server, inbounds_json());
+    let delete_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/2/delClientByEmail/carol/");
+        then.status(200).json_body(ok_response());
+    });
+    let reset_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/1/resetClientTraffic/alice/");
+        then.status(200).json_body(ok_response());
+    });
+
+    let output = run(&cli(&server, &["clients", "delete", "2", "carol"]))
+        .await
+        .unwrap();
+    assert_eq!(output, "Done\n");
+    delete_mock.assert();
+
+    let output = run(&cli(&server, &["clients", "find", "bob"]))
+        .await
+        .unwrap();
+    assert!(output.contains("Inbound:     1 (vmess, vmess port 10001)\n"));
+    assert!(output.contains("Key:         aaaaaaaa-0000-0000-0000-000000000002\n"));
+    assert!(output.contains("Used:        70 B of 1.00 GiB\n"));
+    let output = run(&cli(&server, &["-o", "json", "clients", "find", "carol"]))
+        .await
+        .unwrap();
+    let found: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(found["inboundId"], 2);
+    assert_eq!(found["client"]["password"], "carol-secret");
+    assert_eq!(
+        run(&cli(&server, &["clients", "find", "ghost"]))
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Custom error: Client 'ghost' not found"
+    );
+
+    run(&cli(&server, &["clients", "reset", "1", "alice"]))
+        .await
+        .unwrap();
+    reset_mock.assert();
+}
+
+#[tokio::test]
+async fn test_inbounds_add_and_update_from_files() {
+    let server = setup_mock_server();
+    logged_in_client(&server).await;
+    mock_inbounds(&server, inbounds_json());
+    let add_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/add/")
+            .json_body_partial(r#"{"remark": "socks-in", "port": 20000, "protocol": "socks"}"#);
+        then.status(200)
+            .json_body(json!({"success": true, "msg": "Create Successfully", "obj": null}));
+    });
+    let update_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/update/1/")
+            .json_body_partial(r#"{"id": 1, "remark": "socks-yaml", "port": 20001}"#);
+        then.status(200).json_body(ok_response());
+    });
+
+    let json_file = temp_file(
+        "inbound.json",
+        r#"{"remark": "socks-in", "enable": true, "port": 20000, "protocol": "socks", "settings": {"auth": "noauth", "udp": true}}"#,
+    );
+    let output = run(&cli(&server, &["inbounds", "add", &json_file]))
+        .await
+        .unwrap();
+    assert_eq!(output, "Create Successfully\n");
+    add_mock.assert();
+
+    let yaml_file = temp_file(
+        "inbound.yaml",
+        "remark: socks-yaml\nenable: true\nport: 20001\nprotocol: socks\nsettings:\n  auth: noauth\n",
+    );
+    run(&cli(&server, &["inbounds", "update", "1", &yaml_file]))
+        .await
+        .unwrap();
+    update_mock.assert();
+
+    // The Trojan inbound already listens on 10002
+    let conflicting = temp_file(
+        "conflicting.json",
+        r#"{"remark": "socks-in", "port": 10002, "protocol": "socks", "settings": {"auth": "noauth"}}"#,
+    );
+    let err = run(&cli(&server, &["inbounds", "add", &conflicting]))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.starts_with("Custom error: Invalid inbound:\nerror: port:"));
+    add_mock.assert_hits(1);
+
+    let invalid = temp_file("invalid.yaml", "port: [\n");
+    assert!(
+        run(&cli(&server, &["inbounds", "add", &invalid]))
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid inbound in")
+    );
+
+    for file in [json_file, yaml_file, conflicting, invalid] {
+        std::fs::remove_file(file).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_credentials_from_environment() {
+    let server = setup_mock_server();
+    let login_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/login/")
+            .json_body_partial(r#"{"username": "env-user", "password": "env-pass"}"#);
+        then.status(200)
+            .header("set-cookie", "session=test-cookie; Max-Age=3600; Path=/");
+    });
+    mock_inbounds(&server, inbounds_json());
+    let config = temp_file(
+        "config.yaml",
+        &format!(
+            "panels:\n  - name: de-1\n    url: {}\n    username: admin\n    password: secret\n",
+            server.url("/")
+        ),
+    );
+
+    // The environment overrides the credentials of the profile. No other test reads
+    // these variables: the `cli` helper passes credentials on the command line.
+    unsafe {
+        std::env::set_var("XUI_USERNAME", "env-user");
+        std::env::set_var("XUI_PASSWORD", "env-pass");
+    }
+    let parsed = Cli::try_parse_from(["xui", "--config", &config, "inbounds", "list"]);
+    unsafe {
+        std::env::remove_var("XUI_USERNAME");
+        std::env::remove_var("XUI_PASSWORD");
+    }
+    let parsed = parsed.unwrap();
+
+    let panel = parsed.panel.resolve().unwrap();
+    assert_eq!(panel.name, "de-1");
+    assert_eq!(
+        (panel.username.as_str(), panel.password.as_str()),
+        ("env-user", "env-pass")
+    );
+    run(&parsed).await.unwrap();
+    login_mock.assert();
+    std::fs::remove_file(config).unwrap();
+}
+
+#[test]
+fn test_resolve_requires_credentials() {
+    let config = temp_file(
+        "anonymous.yaml",
+        "panels:\n  - name: de-1\n    url: https://de-1.example.com/\n",
+    );
+    let args = PanelArgs {
+        config: Some(config.clone().into()),
+        ..Default::default()
+    };
+    assert_eq!(
+        args.resolve().unwrap_err().to_string(),
+        "Custom error: No credentials for panel https://de-1.example.com/: set XUI_USERNAME and XUI_PASSWORD"
+    );
+
+    let args = PanelArgs {
+        url: Some("https://nl-1.example.com/".to_string()),
+        username: Some("admin".to_string()),
+        ..Default::default()
+    };
+    assert!(args.resolve().is_err());
+    std::fs::remove_file(config).unwrap();
+}
@@ -0,0 +1,258 @@
+mod common;
+
+use common::*;
+use httpmock::prelude::*;
+use serde_json::json;
+use xui_rs::clients::{AddClientOutcome, ClientPatch};
+use xui_rs::models::Client;
+
+#[tokio::test]
+async fn test_find_client() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+    let inbounds_mock = mock_inbounds(&server, inbounds_json());
+
+    let found = client.find_client("carol").await.unwrap().unwrap();
+    assert_eq!(found.inbound.id, 2);
+    assert_eq!(found.client.password.as_deref(), Some("carol-secret"));
+    assert_eq!(found.traffic.unwrap().down, 60);
+
+    assert!(client.find_client("nobody").await.unwrap().is_none());
+
+    inbounds_mock.assert_hits(2);
+}
+
+#[tokio::test]
+async fn test_find_clients_by_sub_id() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+    let inbounds_mock = mock_inbounds(&server, inbounds_json());
+
+    let found = client.find_clients_by_sub_id("sub-1").await.unwrap();
+    let emails: Vec<&str> = found.iter().map(|f| f.client.email.as_str()).collect();
+    assert_eq!(emails, vec!["alice", "carol"]);
+
+    inbounds_mock.assert();
+}
+
+#[tokio::test]
+async fn test_find_client_by_id() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+    let inbounds_mock = mock_inbounds(&server, inbounds_json());
+
+    let found = client
+        .find_client_by_id("AAAAAAAA-0000-0000-0000-000000000002")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.client.email, "bob");
+    assert_eq!(found.client.limit_ip, 2);
+
+    inbounds_mock.assert();
+}
+
+#[tokio::test]
+async fn test_delete_client_by_email_new_panel() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    let delete_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/2/delClientByEmail/carol/");
+        then.status(200).json_body(ok_response());
+    });
+    let inbound_mock = mock_inbound(&server, 2);
+
+    let response = client.delete_client_by_email(2_u64, "carol").await.unwrap();
+    assert!(response["success"].as_bool().unwrap());
+
+    delete_mock.assert();
+    inbound_mock.assert_hits(0);
+}
+
+#[tokio::test]
+async fn test_delete_client_by_email_fallback() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    let by_email_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/2/delClientByEmail/carol/");
+        then.status(404);
+    });
+    let inbound_mock = mock_inbound(&server, 2);
+    // Trojan clients are keyed by password
+    let delete_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/2/delClient/carol-secret/");
+        then.status(200).json_body(ok_response());
+    });
+
+    let response = client.delete_client_by_email(2_u64, "carol").await.unwrap();
+    assert!(response["success"].as_bool().unwrap());
+
+    by_email_mock.assert();
+    inbound_mock.assert();
+    delete_mock.assert();
+}
+
+#[tokio::test]
+async fn test_update_client_by_email() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    let inbound_mock = mock_inbound(&server, 1);
+    let update_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/updateClient/aaaaaaaa-0000-0000-0000-000000000002/")
+            .body_contains("\\\"limitIp\\\":5");
+        then.status(200).json_body(ok_response());
+    });
+
+    let inbound = client.get_inbound_typed(1_u64).await.unwrap();
+    let mut bob = inbound
+        .clients()
+        .unwrap()
+        .into_iter()
+        .find(|c| c.email == "bob")
+        .unwrap();
+    bob.limit_ip = 5;
+
+    let response = client
+        .update_client_by_email(1_u64, "bob", &bob)
+        .await
+        .unwrap();
+    assert!(response["success"].as_bool().unwrap());
+
+    assert!(
+        client
+            .update_client_by_email(1_u64, "nobody", &bob)
+            .await
+            .is_err()
+    );
+
+    inbound_mock.assert_hits(3);
+    update_mock.assert();
+}
+
+#[tokio::test]
+async fn test_patch_client() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    let inbound_mock = mock_inbound(&server, 1);
+    // Patched fields change, untouched fields (comment, tgId) are sent back unchanged
+    let update_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/updateClient/aaaaaaaa-0000-0000-0000-000000000001/")
+            .body_contains(r#"\"expiryTime\":1767225600000"#)
+            .body_contains(r#"\"enable\":false"#)
+            .body_contains(r#"\"comment\":\"vip\""#)
+            .body_contains(r#"\"tgId\":\"\""#);
+        then.status(200).json_body(ok_response());
+    });
+
+    let patch = ClientPatch {
+        expiry_time: Some(1767225600000),
+        enable: Some(false),
+        ..Default::default()
+    };
+    assert!(!patch.is_empty());
+
+    let updated = client.patch_client(1_u64, "alice", &patch).await.unwrap();
+    assert_eq!(updated.expiry_time, 1767225600000);
+    assert!(!updated.enable);
+    assert_eq!(updated.comment, "vip");
+
+    // The client can also be addressed by its UUID
+    let updated = client
+        .patch_client(1_u64, "aaaaaaaa-0000-0000-0000-000000000001", &patch)
+        .await
+        .unwrap();
+    assert_eq!(updated.email, "alice");
+
+    inbound_mock.assert_hits(2);
+    update_mock.assert_hits(2);
+}
+
+#[tokio::test]
+async fn test_patch_client_panel_failure() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    let inbound_mock = mock_inbound(&server, 2);
+    let update_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/updateClient/carol-secret/");
+        then.status(200)
+            .json_body(json!({ "success": false, "msg": "Something went wrong", "obj": null }));
+    });
+
+    let patch = ClientPatch {
+        limit_ip: Some(1),
+        ..Default::default()
+    };
+    let result = client.patch_client(2_u64, "carol", &patch).await;
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Something went wrong")
+    );
+
+    assert!(client.patch_client(2_u64, "nobody", &patch).await.is_err());
+
+    inbound_mock.assert_hits(2);
+    update_mock.assert();
+}
+
+#[tokio::test]
+async fn test_add_clients() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    let inbounds_mock = mock_inbounds(&server, inbounds_json());
+    let reject_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/addClient/")
+            .body_contains("bad-client");
+        then.status(200)
+            .json_body(json!({ "success": false, "msg": "invalid client", "obj": null }));
+    });
+    let add_mock = server.mock(|when, then| {
+        when.method(POST).path("/panel/api/inbounds/addClient/");
+        then.status(200).json_body(ok_response());
+    });
+
+    let new_client = |email: &str| Client {
+        id: Some(format!("id-{}", email)),
+        email: email.to_string(),
+        ..Default::default()
+    };
+    let clients = vec![
+        new_client("dave"),
+        new_client("bad-client"),
+        new_client("alice"),
+        new_client("dave"),
+        new_client("erin"),
+    ];
+
+    let report = client.add_clients_chunked(1_u64, clients, 2).await.unwrap();
+    let outcomes: Vec<&AddClientOutcome> = report.results.iter().map(|r| &r.outcome).collect();
+    assert_eq!(outcomes[0], &AddClientOutcome::Added);
+    assert!(matches!(outcomes[1], AddClientOutcome::Failed(msg) if msg.contains("invalid client")));
+    assert_eq!(
+        outcomes[2],
+        &AddClientOutcome::EmailExists { inbound_id: 1 }
+    );
+    assert_eq!(outcomes[3], &AddClientOutcome::DuplicateInBatch);
+    assert_eq!(outcomes[4], &AddClientOutcome::Added);
+    assert_eq!(report.added(), vec!["dave", "erin"]);
+    assert!(!report.is_success());
+
+    // Chunk [dave, bad-client] is rejected, then retried one by one; [erin] succeeds
+    inbounds_mock.assert();
+    reject_mock.assert_hits(2);
+    add_mock.assert_hits(2);
+}
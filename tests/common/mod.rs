@@ -0,0 +1,103 @@
+#![allow(dead_code)]
+
+use httpmock::prelude::*;
+use serde_json::{Value, json};
+use xui_rs::api::XUiClient;
+
+// Helper function to set up a mock server
+pub fn setup_mock_server() -> MockServer {
+    MockServer::start()
+}
+
+// Helper mocking the login endpoint and returning a logged-in client
+pub async fn logged_in_client(server: &MockServer) -> XUiClient {
+    server.mock(|when, then| {
+        when.method(POST).path("/login/");
+        then.status(200)
+            .header("set-cookie", "session=test-cookie; Max-Age=3600; Path=/");
+    });
+
+    let mut client = XUiClient::new(server.url("/")).unwrap();
+    client.login("user", "pass").await.unwrap();
+    client
+}
+
+// A VMess inbound with two clients and a Trojan inbound with one client
+pub fn inbounds_json() -> Value {
+    json!([
+        {
+            "id": 1,
+            "remark": "vmess",
+            "enable": true,
+            "expiryTime": 0,
+            "clientStats": [
+                {"id": 1, "inboundId": 1, "enable": true, "email": "alice", "up": 10, "down": 20, "expiryTime": 0, "total": 0, "reset": 0},
+                {"id": 2, "inboundId": 1, "enable": true, "email": "bob", "up": 30, "down": 40, "expiryTime": 0, "total": 0, "reset": 0}
+            ],
+            "listen": "",
+            "port": 10001,
+            "protocol": "vmess",
+            "settings": json!({
+                "clients": [
+                    {"id": "aaaaaaaa-0000-0000-0000-000000000001", "email": "alice", "limitIp": 0, "totalGB": 0, "expiryTime": 0, "enable": true, "tgId": "", "subId": "sub-1", "comment": "vip", "reset": 0},
+                    {"id": "aaaaaaaa-0000-0000-0000-000000000002", "email": "bob", "limitIp": 2, "totalGB": 1073741824_u64, "expiryTime": 0, "enable": true, "tgId": "", "subId": "sub-2", "reset": 0}
+                ]
+            }).to_string(),
+            "streamSettings": json!({"network": "tcp", "security": "none"}).to_string(),
+            "tag": "inbound-10001",
+            "sniffing": ""
+        },
+        {
+            "id": 2,
+            "remark": "trojan",
+            "enable": true,
+            "expiryTime": 0,
+            "clientStats": [
+                {"id": 3, "inboundId": 2, "enable": true, "email": "carol", "up": 50, "down": 60, "expiryTime": 0, "total": 0, "reset": 0}
+            ],
+            "listen": "",
+            "port": 10002,
+            "protocol": "trojan",
+            "settings": json!({
+                "clients": [
+                    {"password": "carol-secret", "email": "carol", "limitIp": 0, "totalGB": 0, "expiryTime": 0, "enable": true, "tgId": "", "subId": "sub-1", "reset": 0}
+                ]
+            }).to_string(),
+            "streamSettings": json!({"network": "tcp", "security": "none"}).to_string(),
+            "tag": "inbound-10002",
+            "sniffing": ""
+        }
+    ])
+}
+
+// Helper mocking the inbound list endpoint with the given inbounds
+pub fn mock_inbounds<'a>(server: &'a MockServer, inbounds: Value) -> httpmock::Mock<'a> {
+    server.mock(|when, then| {
+        when.method(GET).path("/panel/api/inbounds/list/");
+        then.status(200)
+            .json_body(json!({ "success": true, "msg": "", "obj": inbounds }));
+    })
+}
+
+// Successful response without payload
+pub fn ok_response() -> Value {
+    json!({ "success": true, "msg": "", "obj": null })
+}
+
+// Helper mocking the single inbound endpoint with the inbound of `inbounds_json` with this ID
+pub fn mock_inbound(server: &MockServer, id: u64) -> httpmock::Mock<'_> {
+    let inbound = inbounds_json()
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|inbound| inbound["id"] == id)
+        .cloned()
+        .unwrap();
+
+    server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/panel/api/inbounds/get/{}/", id));
+        then.status(200)
+            .json_body(json!({ "success": true, "msg": "", "obj": inbound }));
+    })
+}
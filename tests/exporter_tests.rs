@@ -0,0 +1,123 @@
+#![cfg(feature = "exporter")]
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::*;
+use httpmock::prelude::*;
+use serde_json::json;
+use tokio::net::TcpListener;
+use xui_rs::api::XUiClient;
+use xui_rs::exporter::{collect, render, serve};
+use xui_rs::fleet::Fleet;
+
+async fn fleet(server: &MockServer) -> Fleet {
+    let mut inbounds = inbounds_json();
+    inbounds[0]["remark"] = json!("vmess \"main\"");
+    mock_inbounds(server, inbounds);
+    server.mock(|when, then| {
+        when.method(POST).path("/panel/api/inbounds/onlines/");
+        then.status(200)
+            .json_body(json!({"success": true, "msg": "", "obj": ["alice"]}));
+    });
+    server.mock(|when, then| {
+        when.method(POST).path("/server/status/");
+        then.status(200).json_body(json!({
+            "success": true,
+            "msg": "",
+            "obj": {"cpu": 12.5, "mem": {"current": 512, "total": 2048}, "xray": {"state": "running"}}
+        }));
+    });
+
+    let mut fleet = Fleet::new();
+    fleet.add("de-1", logged_in_client(server).await);
+    // Nothing listens on port 1
+    fleet.add(
+        "nl-1",
+        XUiClient::new("http://127.0.0.1:1/")
+            .unwrap()
+            .with_credentials("admin", "secret"),
+    );
+    fleet
+}
+
+#[tokio::test]
+async fn test_render_metrics() {
+    let server = setup_mock_server();
+    let mut fleet = fleet(&server).await;
+
+    let metrics = render(&collect(&mut fleet).await);
+
+    assert!(metrics.contains("# TYPE xui_up gauge\n"));
+    assert!(metrics.contains("xui_up{panel=\"de-1\"} 1\n"));
+    assert!(metrics.contains("xui_up{panel=\"nl-1\"} 0\n"));
+    assert!(metrics.contains(
+        "xui_inbound_enabled{panel=\"de-1\",inbound_id=\"1\",remark=\"vmess \\\"main\\\"\",protocol=\"vmess\",port=\"10001\"} 1\n"
+    ));
+    assert!(
+        metrics.contains(
+            "xui_client_down_bytes{panel=\"de-1\",inbound_id=\"1\",email=\"alice\"} 20\n"
+        )
+    );
+    assert!(
+        metrics.contains("xui_client_online{panel=\"de-1\",inbound_id=\"1\",email=\"alice\"} 1\n")
+    );
+    assert!(
+        metrics.contains("xui_client_online{panel=\"de-1\",inbound_id=\"1\",email=\"bob\"} 0\n")
+    );
+    assert!(metrics.contains("xui_online_clients{panel=\"de-1\"} 1\n"));
+    assert!(metrics.contains("xui_server_cpu_percent{panel=\"de-1\"} 12.5\n"));
+    assert!(metrics.contains("xui_xray_running{panel=\"de-1\"} 1\n"));
+    assert!(!metrics.contains("panel=\"nl-1\",inbound_id"));
+}
+
+#[tokio::test]
+async fn test_serve_metrics() {
+    let server = setup_mock_server();
+    let fleet = fleet(&server).await;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let scrape = async {
+        let metrics = reqwest::get(format!("http://{}/metrics", address))
+            .await
+            .unwrap();
+        assert_eq!(metrics.status(), 200);
+        let body = metrics.text().await.unwrap();
+        let missing = reqwest::get(format!("http://{}/other", address))
+            .await
+            .unwrap();
+        (body, missing.status())
+    };
+
+    tokio::select! {
+        result = serve(fleet, listener) => panic!("server stopped: {:?}", result.err()),
+        (body, missing) = scrape => {
+            assert!(body.contains("xui_client_up_bytes{panel=\"de-1\",inbound_id=\"2\",email=\"carol\"} 50\n"));
+            assert_eq!(missing, 404);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_unresponsive_panel_is_down() {
+    let server = setup_mock_server();
+    let stalled = setup_mock_server();
+    let mut fleet = fleet(&server).await;
+    stalled.mock(|when, then| {
+        when.method(GET).path("/panel/api/inbounds/list/");
+        then.status(200)
+            .delay(Duration::from_secs(30))
+            .json_body(json!({"success": true, "msg": "", "obj": []}));
+    });
+    fleet.add("fr-1", logged_in_client(&stalled).await);
+    let mut fleet = fleet.timeout(Duration::from_millis(500));
+
+    let started = Instant::now();
+    let metrics = render(&collect(&mut fleet).await);
+
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(metrics.contains("xui_up{panel=\"fr-1\"} 0\n"));
+    assert!(metrics.contains("xui_up{panel=\"de-1\"} 1\n"));
+}
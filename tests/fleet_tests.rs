@@ -0,0 +1,108 @@
+mod common;
+
+use common::*;
+use httpmock::prelude::*;
+use serde_json::json;
+use xui_rs::fleet::{Fleet, FleetConfig};
+
+fn mock_login(server: &MockServer) -> httpmock::Mock<'_> {
+    server.mock(|when, then| {
+        when.method(POST).path("/login/");
+        then.status(200)
+            .header("set-cookie", "session=test-cookie; Max-Age=3600; Path=/");
+    })
+}
+
+fn fleet(healthy: &MockServer, broken: &MockServer) -> Fleet {
+    let config = FleetConfig::from_yaml(&format!(
+        "panels:\n  - name: de-1\n    url: {}\n    username: admin\n    password: secret\n  - name: nl-1\n    url: {}\n    username: admin\n    password: secret\n",
+        healthy.url("/"),
+        broken.url("/")
+    ))
+    .unwrap();
+    Fleet::from_config(&config).unwrap()
+}
+
+#[tokio::test]
+async fn test_fleet_fan_out_tolerates_failures() {
+    let healthy = setup_mock_server();
+    let broken = setup_mock_server();
+    let login_mock = mock_login(&healthy);
+    mock_inbounds(&healthy, inbounds_json());
+    let broken_login_mock = broken.mock(|when, then| {
+        when.method(POST).path("/login/");
+        then.status(401);
+    });
+
+    let mut fleet = fleet(&healthy, &broken).concurrency(2);
+    assert_eq!(fleet.names(), ["de-1", "nl-1"]);
+
+    let inbounds = fleet.get_inbounds_all().await;
+    assert_eq!(inbounds["de-1"].as_ref().unwrap().len(), 2);
+    assert!(inbounds["nl-1"].is_err());
+
+    let found = fleet.find_client_everywhere("carol").await;
+    let carol = found["de-1"].as_ref().unwrap().as_ref().unwrap();
+    assert_eq!(carol.inbound.id, 2);
+    assert!(found["nl-1"].is_err());
+
+    // The healthy panel logged in once and reused its session
+    login_mock.assert_hits(1);
+    broken_login_mock.assert_hits(2);
+}
+
+#[tokio::test]
+async fn test_fleet_server_status() {
+    let healthy = setup_mock_server();
+    let broken = setup_mock_server();
+    mock_login(&healthy);
+    mock_login(&broken);
+    healthy.mock(|when, then| {
+        when.method(POST).path("/server/status/");
+        then.status(200).json_body(json!({
+            "success": true,
+            "msg": "",
+            "obj": {
+                "cpu": 12.5,
+                "cpuCores": 4,
+                "mem": {"current": 1024, "total": 4096},
+                "xray": {"state": "running", "errorMsg": "", "version": "25.1.1"},
+                "uptime": 3600,
+                "loads": [0.1, 0.2, 0.3],
+                "netIO": {"up": 10, "down": 20},
+                "netTraffic": {"sent": 100, "recv": 200},
+                "publicIP": {"ipv4": "1.2.3.4", "ipv6": "N/A"},
+                "appStats": {"threads": 10, "mem": 1000, "uptime": 60}
+            }
+        }));
+    });
+    broken.mock(|when, then| {
+        when.method(POST).path("/server/status/");
+        then.status(200)
+            .json_body(json!({"success": false, "msg": "boom", "obj": null}));
+    });
+
+    let mut fleet = fleet(&healthy, &broken);
+    let statuses = fleet.server_status_all().await;
+
+    let status = statuses["de-1"].as_ref().unwrap();
+    assert!(status.xray.is_running());
+    assert_eq!(status.mem.total, 4096);
+    assert_eq!(status.net_io.down, 20);
+    assert_eq!(status.public_ip.ipv4, "1.2.3.4");
+    assert_eq!(status.extra["appStats"]["threads"], 10);
+    assert!(statuses["nl-1"].is_err());
+}
+
+#[test]
+fn test_fleet_requires_credentials() {
+    let config = FleetConfig::from_yaml(
+        "panels:\n  - name: de-1\n    url: https://de-1.example.com/\n    username: admin\n",
+    )
+    .unwrap();
+    let err = Fleet::from_config(&config).err().unwrap();
+    assert!(
+        err.to_string()
+            .contains("Panel 'de-1' has no username or password")
+    );
+}
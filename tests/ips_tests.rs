@@ -0,0 +1,143 @@
+mod common;
+
+use std::time::Duration;
+
+use common::*;
+use httpmock::prelude::*;
+use serde_json::json;
+use xui_rs::bulk::BulkOutcome;
+use xui_rs::ips::{ClientIp, IpAction, IpAnalyzer, parse_client_ips};
+
+fn ip(ip: &str, last_seen: Option<i64>) -> ClientIp {
+    ClientIp {
+        ip: ip.to_string(),
+        last_seen,
+    }
+}
+
+#[test]
+fn test_parse_client_ips() {
+    let ips = parse_client_ips(json!({
+        "success": true,
+        "msg": "",
+        "obj": "[\"1.1.1.1 (2024-05-01 10:00:00)\", \"2.2.2.2\"]"
+    }))
+    .unwrap();
+    assert_eq!(
+        ips,
+        [ip("1.1.1.1", Some(1_714_557_600_000)), ip("2.2.2.2", None)]
+    );
+
+    let ips = parse_client_ips(json!({
+        "success": true,
+        "obj": [{"ip": "10.0.0.1", "timestamp": 1_714_557_600}]
+    }))
+    .unwrap();
+    assert_eq!(ips, [ip("10.0.0.1", Some(1_714_557_600_000))]);
+
+    // Timestamps out of range are dropped, not wrapped
+    let ips = parse_client_ips(json!({
+        "success": true,
+        "obj": [
+            {"ip": "10.0.0.2", "timestamp": i64::MIN},
+            "10.0.0.3 (9999999999999999-01-01 00:00:00)",
+            "10.0.0.4 (2024-13-01 00:00:00)"
+        ]
+    }))
+    .unwrap();
+    assert_eq!(
+        ips,
+        [
+            ip("10.0.0.2", None),
+            ip("10.0.0.3", None),
+            ip("10.0.0.4", None)
+        ]
+    );
+
+    let none = parse_client_ips(json!({"success": true, "msg": "", "obj": "No IP Record"}));
+    assert!(none.unwrap().is_empty());
+}
+
+#[test]
+fn test_offending_ips_window() {
+    let analyzer = IpAnalyzer::new(2).window(Duration::from_secs(60));
+    let now = 1_000_000;
+    let ips = [
+        ip("1.1.1.1", Some(now - 10_000)),
+        ip("1.1.1.1", Some(now - 5_000)),
+        ip("2.2.2.2", Some(now - 20_000)),
+        // Outside the window
+        ip("3.3.3.3", Some(now - 120_000)),
+    ];
+    assert!(analyzer.offending_ips(&ips, 2, now).is_none());
+
+    // IPs without timestamp always count
+    let mut ips = ips.to_vec();
+    ips.push(ip("4.4.4.4", None));
+    let offending = analyzer.offending_ips(&ips, 2, now).unwrap();
+    let addresses: Vec<&str> = offending.iter().map(|ip| ip.ip.as_str()).collect();
+    assert_eq!(addresses, ["1.1.1.1", "2.2.2.2", "4.4.4.4"]);
+    assert_eq!(offending[0].last_seen, Some(now - 5_000));
+
+    // A window too large for the clock keeps every IP
+    let unbounded = IpAnalyzer::new(2).window(Duration::MAX);
+    assert_eq!(unbounded.offending_ips(&ips, 2, now).unwrap().len(), 4);
+
+    assert_eq!(IpAnalyzer::new(2).use_client_limit(true).limit_for(5), 5);
+    assert_eq!(IpAnalyzer::new(2).limit_for(5), 2);
+}
+
+#[tokio::test]
+async fn test_ip_analyzer_run() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+    mock_inbounds(&server, inbounds_json());
+
+    // alice and carol are within limits, bob (limitIp 2) is not
+    for email in ["alice", "carol"] {
+        server.mock(|when, then| {
+            when.method(POST)
+                .path(format!("/panel/api/inbounds/clientIps/{}/", email));
+            then.status(200)
+                .json_body(json!({"success": true, "msg": "", "obj": "No IP Record"}));
+        });
+    }
+    server.mock(|when, then| {
+        when.method(POST).path("/panel/api/inbounds/clientIps/bob/");
+        then.status(200).json_body(json!({
+            "success": true,
+            "msg": "",
+            "obj": "[\"1.1.1.1\", \"2.2.2.2\", \"3.3.3.3\"]"
+        }));
+    });
+    let clear_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/clearClientIps/bob/");
+        then.status(200).json_body(ok_response());
+    });
+
+    let report = IpAnalyzer::new(5)
+        .use_client_limit(true)
+        .action(IpAction::ClearIps)
+        .run(&mut client)
+        .await
+        .unwrap();
+
+    assert_eq!(report.checked, 3);
+    assert_eq!(report.offenders.len(), 1);
+    let offender = &report.offenders[0];
+    assert_eq!((offender.email.as_str(), offender.limit), ("bob", 2));
+    assert_eq!(offender.ips.len(), 3);
+    assert_eq!(offender.actions[0].outcome, BulkOutcome::Done);
+    clear_mock.assert();
+
+    // A dry run only plans the actions
+    let report = IpAnalyzer::new(1)
+        .action(IpAction::Disable)
+        .dry_run(true)
+        .run(&mut client)
+        .await
+        .unwrap();
+    assert_eq!(report.offenders[0].actions[0].outcome, BulkOutcome::Planned);
+    clear_mock.assert_hits(1);
+}
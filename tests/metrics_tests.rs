@@ -0,0 +1,128 @@
+mod common;
+use common::*;
+
+use httpmock::prelude::*;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use xui_rs::api::XUiClient;
+use xui_rs::metrics::{ErrorKind, RequestMetrics, RequestRecord};
+
+#[derive(Default)]
+struct Recorder {
+    records: Mutex<Vec<RequestRecord>>,
+}
+
+impl RequestMetrics for Recorder {
+    fn record(&self, request: &RequestRecord) {
+        self.records.lock().unwrap().push(request.clone());
+    }
+}
+
+#[tokio::test]
+async fn test_metrics_record_requests_and_errors() {
+    let server = setup_mock_server();
+    server.mock(|when, then| {
+        when.method(POST).path("/login/");
+        then.status(200)
+            .header("set-cookie", "session=test-cookie; Max-Age=3600; Path=/");
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/panel/api/inbounds/list/");
+        then.status(200)
+            .json_body(json!({"success": true, "msg": "", "obj": []}));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/panel/api/inbounds/get/7/");
+        then.status(500).body("internal error");
+    });
+
+    let recorder = Arc::new(Recorder::default());
+    let mut client = XUiClient::new(server.url("/"))
+        .unwrap()
+        .with_credentials("user", "pass")
+        .with_metrics(recorder.clone());
+
+    client.get_inbounds().await.unwrap();
+    assert!(client.get_inbound(7_u64).await.is_err());
+
+    let records = recorder.records.lock().unwrap();
+    let summary: Vec<_> = records
+        .iter()
+        .map(|r| {
+            (
+                r.method.as_str(),
+                r.endpoint.as_str(),
+                r.status,
+                r.relogin,
+                r.error,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("POST", "/login/", Some(200), false, None),
+            ("GET", "/panel/api/inbounds/list/", Some(200), true, None),
+            (
+                "GET",
+                "/panel/api/inbounds/get/7/",
+                Some(500),
+                false,
+                Some(ErrorKind::Status)
+            ),
+        ]
+    );
+    assert!(
+        records
+            .iter()
+            .all(|r| r.host == "127.0.0.1" && r.retries == 0)
+    );
+}
+
+#[tokio::test]
+async fn test_relogin_and_retry_on_unauthorized() {
+    let server = setup_mock_server();
+    let mut old_login = server.mock(|when, then| {
+        when.method(POST).path("/login/");
+        then.status(200).header("set-cookie", "session=old; Path=/");
+    });
+
+    let recorder = Arc::new(Recorder::default());
+    let mut client = XUiClient::new(server.url("/"))
+        .unwrap()
+        .with_metrics(recorder.clone());
+    client.login("user", "pass").await.unwrap();
+    old_login.delete();
+
+    let new_login = server.mock(|when, then| {
+        when.method(POST).path("/login/");
+        then.status(200).header("set-cookie", "session=new; Path=/");
+    });
+    let rejected = server.mock(|when, then| {
+        when.method(GET)
+            .path("/panel/api/inbounds/list/")
+            .header("cookie", "session=old; Path=/");
+        then.status(401);
+    });
+    let accepted = server.mock(|when, then| {
+        when.method(GET)
+            .path("/panel/api/inbounds/list/")
+            .header("cookie", "session=new; Path=/");
+        then.status(200)
+            .json_body(json!({"success": true, "msg": "", "obj": []}));
+    });
+
+    let inbounds = client.get_inbounds().await.unwrap();
+    assert_eq!(inbounds["obj"], json!([]));
+    new_login.assert();
+    rejected.assert();
+    accepted.assert();
+
+    let records = recorder.records.lock().unwrap();
+    let last = records.last().unwrap();
+    assert_eq!(last.endpoint, "/panel/api/inbounds/list/");
+    assert_eq!(last.status, Some(200));
+    assert_eq!(last.retries, 1);
+    assert!(last.relogin);
+    assert!(last.is_success());
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::*;
+use httpmock::prelude::*;
+use serde_json::json;
+use xui_rs::migrate::{MigrateOptions, migrate_client};
+
+#[tokio::test]
+async fn test_migrate_client_to_other_panel() {
+    let source = setup_mock_server();
+    let target = setup_mock_server();
+    let mut source_client = logged_in_client(&source).await;
+    let mut target_client = logged_in_client(&target).await;
+
+    mock_inbound(&source, 1);
+    mock_inbound(&target, 2);
+
+    // bob used 70 bytes of his 1 GiB quota; his UUID becomes the Trojan password
+    let add_mock = target.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/addClient/")
+            .body_contains(r#"\"password\":\"aaaaaaaa-0000-0000-0000-000000000002\""#)
+            .body_contains(r#"\"totalGB\":1073741754"#)
+            .body_contains(r#"\"subId\":\"sub-2\""#);
+        then.status(200).json_body(ok_response());
+    });
+    let delete_mock = source.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/1/delClient/aaaaaaaa-0000-0000-0000-000000000002/");
+        then.status(200).json_body(ok_response());
+    });
+
+    let options = MigrateOptions::new().delete_source(true);
+    let migrated = migrate_client(
+        (&mut source_client, 1, "bob"),
+        (&mut target_client, 2),
+        &options,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(migrated.used, 70);
+    assert_eq!(migrated.client.total_gb, 1073741754);
+    assert!(migrated.client.id.is_none());
+    assert!(migrated.source_deleted);
+
+    add_mock.assert();
+    delete_mock.assert();
+}
+
+#[tokio::test]
+async fn test_migrate_client_rolls_back() {
+    let source = setup_mock_server();
+    let target = setup_mock_server();
+    let mut source_client = logged_in_client(&source).await;
+    let mut target_client = logged_in_client(&target).await;
+
+    mock_inbound(&source, 2);
+    mock_inbound(&target, 1);
+
+    // carol's Trojan password is not a UUID, so she gets a new one on the VMess inbound
+    let add_mock = target.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/addClient/")
+            .body_contains(r#"\"email\":\"carol\""#);
+        then.status(200).json_body(ok_response());
+    });
+    let delete_source_mock = source.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/2/delClient/carol-secret/");
+        then.status(200)
+            .json_body(json!({"success": false, "msg": "locked", "obj": null}));
+    });
+    let rollback_mock = target.mock(|when, then| {
+        when.method(POST)
+            .path_contains("/panel/api/inbounds/1/delClient/");
+        then.status(200).json_body(ok_response());
+    });
+
+    let options = MigrateOptions::new().delete_source(true);
+    let err = migrate_client(
+        (&mut source_client, 2, "carol"),
+        (&mut target_client, 1),
+        &options,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("rolled back"));
+
+    add_mock.assert();
+    delete_source_mock.assert();
+    rollback_mock.assert();
+}
+
+#[tokio::test]
+async fn test_migrate_within_panel_requires_delete() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+    let mut same = client.clone();
+
+    let result = migrate_client(
+        (&mut client, 1, "bob"),
+        (&mut same, 2),
+        &MigrateOptions::new(),
+    )
+    .await;
+    assert!(result.is_err());
+}
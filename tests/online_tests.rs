@@ -0,0 +1,110 @@
+mod common;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use common::*;
+use futures::StreamExt;
+use httpmock::prelude::*;
+use serde_json::json;
+use xui_rs::online::{OnlineEvent, OnlineTracker, OnlineWatcher};
+
+fn emails(emails: &[&str]) -> Vec<String> {
+    emails.iter().map(|email| email.to_string()).collect()
+}
+
+#[test]
+fn test_online_tracker() {
+    let mut tracker = OnlineTracker::new();
+    let events = tracker.update(&emails(&["alice", "bob"]), &HashMap::new(), 1_000);
+    assert_eq!(events.len(), 2);
+    assert!(
+        events
+            .iter()
+            .all(|e| matches!(e, OnlineEvent::ClientConnected { .. }))
+    );
+
+    assert!(
+        tracker
+            .update(&emails(&["bob", "alice"]), &HashMap::new(), 2_000)
+            .is_empty()
+    );
+
+    let last_online = HashMap::from([("alice".to_string(), 2_500)]);
+    let events = tracker.update(&emails(&["bob", "carol"]), &last_online, 3_000);
+    assert_eq!(
+        events,
+        [
+            OnlineEvent::ClientDisconnected {
+                email: "alice".to_string(),
+                timestamp: 2_500
+            },
+            OnlineEvent::ClientConnected {
+                email: "carol".to_string(),
+                timestamp: 3_000
+            },
+        ]
+    );
+    assert_eq!(tracker.online().collect::<Vec<_>>(), ["bob", "carol"]);
+}
+
+#[tokio::test]
+async fn test_online_watcher_stream() {
+    let server = setup_mock_server();
+    let client = logged_in_client(&server).await;
+
+    let mut inbounds = inbounds_json();
+    inbounds[0]["clientStats"][0]["lastOnline"] = json!(1_700_000_000_000_i64);
+    let list_mock = mock_inbounds(&server, inbounds);
+    let mut onlines_mock = server.mock(|when, then| {
+        when.method(POST).path("/panel/api/inbounds/onlines/");
+        then.status(200)
+            .json_body(json!({"success": true, "msg": "", "obj": ["alice"]}));
+    });
+
+    let mut events = Box::pin(
+        OnlineWatcher::new(client)
+            .interval(Duration::from_millis(10))
+            .into_stream(),
+    );
+    let event = events.next().await.unwrap().unwrap();
+    assert!(matches!(event, OnlineEvent::ClientConnected { ref email, .. } if email == "alice"));
+    // No disconnection yet, so client stats were not needed
+    list_mock.assert_hits(0);
+
+    onlines_mock.delete();
+    server.mock(|when, then| {
+        when.method(POST).path("/panel/api/inbounds/onlines/");
+        then.status(200)
+            .json_body(json!({"success": true, "msg": "", "obj": null}));
+    });
+    let event = events.next().await.unwrap().unwrap();
+    assert_eq!(
+        event,
+        OnlineEvent::ClientDisconnected {
+            email: "alice".to_string(),
+            timestamp: 1_700_000_000_000
+        }
+    );
+    list_mock.assert_hits(1);
+}
+
+#[tokio::test]
+async fn test_online_watcher_zero_interval() {
+    let server = setup_mock_server();
+    let client = logged_in_client(&server).await;
+    server.mock(|when, then| {
+        when.method(POST).path("/panel/api/inbounds/onlines/");
+        then.status(200)
+            .json_body(json!({"success": true, "msg": "", "obj": ["carol"]}));
+    });
+
+    // A zero interval is raised to 1 ms instead of panicking
+    let mut events = Box::pin(
+        OnlineWatcher::new(client)
+            .interval(Duration::ZERO)
+            .into_stream(),
+    );
+    let event = events.next().await.unwrap().unwrap();
+    assert!(matches!(event, OnlineEvent::ClientConnected { ref email, .. } if email == "carol"));
+}
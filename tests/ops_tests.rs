@@ -0,0 +1,141 @@
+mod common;
+
+use common::*;
+use httpmock::prelude::*;
+use serde_json::json;
+use xui_rs::ops::{extended_expiry, renewed_expiry};
+
+const DAY: i64 = 24 * 60 * 60 * 1000;
+const NOW: i64 = 1_700_000_000_000;
+
+#[test]
+fn test_extended_expiry() {
+    // Unlimited stays unlimited
+    assert_eq!(extended_expiry(0, 30, NOW), 0);
+    // "Start on first use" gets a longer duration
+    assert_eq!(extended_expiry(-7 * DAY, 30, NOW), -37 * DAY);
+    // A future expiry is extended from its current value
+    assert_eq!(extended_expiry(NOW + DAY, 30, NOW), NOW + 31 * DAY);
+    // An expired client is extended from now
+    assert_eq!(extended_expiry(NOW - 10 * DAY, 30, NOW), NOW + 30 * DAY);
+}
+
+#[test]
+fn test_renewed_expiry() {
+    assert_eq!(renewed_expiry(-7 * DAY, 30, NOW), -30 * DAY);
+    assert_eq!(renewed_expiry(NOW + 5 * DAY, 30, NOW), NOW + 30 * DAY);
+    assert_eq!(renewed_expiry(NOW - 5 * DAY, 30, NOW), NOW + 30 * DAY);
+    // Unlimited stays unlimited, as in `extended_expiry`
+    assert_eq!(renewed_expiry(0, 30, NOW), 0);
+    // A period of zero days changes nothing, "start on first use" included
+    assert_eq!(renewed_expiry(-7 * DAY, 0, NOW), -7 * DAY);
+    assert_eq!(renewed_expiry(NOW + 5 * DAY, 0, NOW), NOW + 5 * DAY);
+}
+
+#[tokio::test]
+async fn test_suspend_and_add_quota() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    let inbound_mock = mock_inbound(&server, 1);
+    let suspend_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/updateClient/aaaaaaaa-0000-0000-0000-000000000002/")
+            .body_contains(r#"\"enable\":false"#);
+        then.status(200).json_body(ok_response());
+    });
+    let quota_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/updateClient/aaaaaaaa-0000-0000-0000-000000000002/")
+            .body_contains(r#"\"totalGB\":2147483648"#);
+        then.status(200).json_body(ok_response());
+    });
+
+    let mut ops = client.ops();
+    let suspended = ops.suspend(1_u64, "bob").await.unwrap();
+    assert!(!suspended.enable);
+
+    let topped_up = ops.add_quota_bytes(1_u64, "bob", 1073741824).await.unwrap();
+    assert_eq!(topped_up.total_gb, 2147483648);
+
+    // alice has an unlimited quota, which stays unlimited
+    let unlimited_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/updateClient/aaaaaaaa-0000-0000-0000-000000000001/")
+            .body_contains(r#"\"totalGB\":0"#);
+        then.status(200).json_body(ok_response());
+    });
+    let unlimited = ops
+        .add_quota_bytes(1_u64, "alice", 1073741824)
+        .await
+        .unwrap();
+    assert_eq!(unlimited.total_gb, 0);
+
+    inbound_mock.assert_hits(3);
+    suspend_mock.assert();
+    quota_mock.assert();
+    unlimited_mock.assert();
+}
+
+#[tokio::test]
+async fn test_renew_period() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    let inbound_mock = mock_inbound(&server, 2);
+    let update_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/updateClient/carol-secret/")
+            .body_contains(r#"\"totalGB\":5000"#)
+            .body_contains(r#"\"enable\":true"#);
+        then.status(200).json_body(ok_response());
+    });
+    let reset_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/2/resetClientTraffic/carol/");
+        then.status(200).json_body(ok_response());
+    });
+
+    let renewed = client
+        .ops()
+        .renew_period(2_u64, "carol", 30, Some(5000))
+        .await
+        .unwrap();
+    // carol never expires
+    assert_eq!(renewed.expiry_time, 0);
+    assert_eq!(renewed.total_gb, 5000);
+
+    inbound_mock.assert();
+    update_mock.assert();
+    reset_mock.assert();
+}
+
+#[tokio::test]
+async fn test_renew_period_reports_failed_reset() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    mock_inbound(&server, 2);
+    let update_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/updateClient/carol-secret/");
+        then.status(200).json_body(ok_response());
+    });
+    server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/2/resetClientTraffic/carol/");
+        then.status(200)
+            .json_body(json!({"success": false, "msg": "reset failed", "obj": null}));
+    });
+
+    let err = client
+        .ops()
+        .renew_period(2_u64, "carol", 30, None)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Client 'carol' was renewed but its traffic was not reset")
+    );
+    update_mock.assert();
+}
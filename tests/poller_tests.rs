@@ -0,0 +1,114 @@
+mod common;
+
+use std::time::Duration;
+
+use common::*;
+use futures::StreamExt;
+use serde_json::{Value, json};
+use xui_rs::models::{Inbound, parse_inbounds};
+use xui_rs::poller::{TrafficPoller, TrafficTracker};
+
+fn inbounds(value: Value) -> Vec<Inbound> {
+    parse_inbounds(json!({ "success": true, "msg": "", "obj": value })).unwrap()
+}
+
+// `inbounds_json` with alice's counters and the VMess inbound's counters replaced
+fn with_counters(alice: (u64, u64), inbound: (u64, u64)) -> Value {
+    let mut value = inbounds_json();
+    value[0]["clientStats"][0]["up"] = json!(alice.0);
+    value[0]["clientStats"][0]["down"] = json!(alice.1);
+    value[0]["up"] = json!(inbound.0);
+    value[0]["down"] = json!(inbound.1);
+    value
+}
+
+#[test]
+fn test_traffic_tracker_deltas_and_resets() {
+    let mut tracker = TrafficTracker::new();
+    assert!(
+        tracker
+            .update(&inbounds(with_counters((10, 20), (100, 200))), 1_000)
+            .is_none()
+    );
+
+    let sample = tracker
+        .update(&inbounds(with_counters((15, 50), (150, 300))), 61_000)
+        .unwrap();
+    assert_eq!((sample.from, sample.to), (1_000, 61_000));
+    let alice = &sample.clients[0];
+    assert_eq!(
+        (alice.email.as_str(), alice.up, alice.down),
+        ("alice", 5, 30)
+    );
+    assert!(!alice.reset);
+    assert_eq!(sample.clients[1].total(), 0);
+    assert_eq!(sample.inbounds[0].total(), 150);
+
+    // alice's traffic was reset and she used 4 bytes since
+    let mut value = with_counters((1, 3), (160, 300));
+    value[0]["clientStats"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({"id": 9, "inboundId": 1, "enable": true, "email": "dave", "up": 7, "down": 0, "expiryTime": 0, "total": 0, "reset": 0}));
+    let sample = tracker.update(&inbounds(value), 121_000).unwrap();
+    let alice = &sample.clients[0];
+    assert!(alice.reset);
+    assert_eq!(alice.total(), 4);
+    assert_eq!(sample.inbounds[0].up, 10);
+    // A new client counts entirely
+    let dave = sample.clients.iter().find(|c| c.email == "dave").unwrap();
+    assert_eq!(dave.up, 7);
+}
+
+#[tokio::test]
+async fn test_traffic_poller() {
+    let server = setup_mock_server();
+    let client = logged_in_client(&server).await;
+    let mut poller = TrafficPoller::new(client);
+
+    let mut list_mock = mock_inbounds(&server, with_counters((10, 20), (0, 0)));
+    assert!(poller.poll().await.unwrap().is_none());
+    list_mock.delete();
+
+    list_mock = mock_inbounds(&server, with_counters((110, 20), (0, 0)));
+    let sample = poller.poll().await.unwrap().unwrap();
+    assert_eq!(sample.clients[0].up, 100);
+    list_mock.assert();
+
+    // The stream continues from the recorded baseline, one sample per interval
+    let mut stream = Box::pin(poller.interval(Duration::from_millis(10)).into_stream());
+    let sample = stream.next().await.unwrap().unwrap();
+    assert_eq!(sample.clients[0].total(), 0);
+}
+
+#[tokio::test]
+async fn test_traffic_poller_spawn_without_buffer() {
+    let server = setup_mock_server();
+    let client = logged_in_client(&server).await;
+    mock_inbounds(&server, with_counters((10, 20), (0, 0)));
+
+    // A zero buffer is raised to one instead of panicking
+    let (mut receiver, handle) = TrafficPoller::new(client)
+        .interval(Duration::from_millis(10))
+        .spawn(0);
+    let sample = receiver.recv().await.unwrap().unwrap();
+    assert_eq!(sample.clients[0].up, 0);
+    drop(receiver);
+    handle.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_traffic_poller_zero_interval() {
+    let server = setup_mock_server();
+    let client = logged_in_client(&server).await;
+    mock_inbounds(&server, with_counters((10, 20), (0, 0)));
+
+    // A zero interval is raised to 1 ms instead of panicking
+    let mut stream = Box::pin(
+        TrafficPoller::new(client)
+            .interval(Duration::ZERO)
+            .into_stream(),
+    );
+    let sample = stream.next().await.unwrap().unwrap();
+    assert_eq!(sample.clients[0].total(), 0);
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "qr")]
+
+use xui_rs::links::{ShareLink, StreamParams};
+use xui_rs::models::Protocol;
+use xui_rs::qr::LinkQrCode;
+
+fn share_link() -> ShareLink {
+    ShareLink {
+        protocol: Protocol::Trojan,
+        address: "vpn.example.com".to_string(),
+        port: 443,
+        credential: "secret".to_string(),
+        remark: "trojan-alice".to_string(),
+        stream: StreamParams {
+            network: "tcp".to_string(),
+            security: "tls".to_string(),
+            sni: "vpn.example.com".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_qr_png() {
+    let qr = LinkQrCode::from_share_link(&share_link()).unwrap();
+    let png = qr.to_png(4).unwrap();
+
+    // PNG signature followed by an IHDR chunk with the expected dimensions
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    let side = ((qr.width() + 8) * 4) as u32;
+    assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), side);
+    assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()), side);
+}
+
+#[test]
+fn test_qr_svg_and_terminal() {
+    let qr = LinkQrCode::new("https://vpn.example.com:2096/sub/k8xq2nz7a1b2c3d4").unwrap();
+
+    let svg = qr.to_svg(2);
+    assert!(svg.contains("<svg"));
+    assert!(svg.ends_with("</svg>"));
+
+    let terminal = qr.to_terminal();
+    assert!(terminal.contains('\u{2580}') || terminal.contains('\u{2584}'));
+    assert_eq!(terminal.lines().count(), (qr.width() + 8).div_ceil(2));
+}
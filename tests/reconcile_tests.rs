@@ -0,0 +1,280 @@
+mod common;
+
+use common::*;
+use httpmock::prelude::*;
+use serde_json::json;
+use xui_rs::models::{Inbound, parse_inbounds};
+use xui_rs::reconcile::{Change, PanelState, Reconciler};
+
+const DESIRED: &str = r#"
+inbounds:
+  - remark: vmess-renamed
+    port: 10001
+    protocol: vmess
+    streamSettings:
+      network: ws
+    clients:
+      - email: alice
+        id: aaaaaaaa-0000-0000-0000-000000000001
+        comment: vip
+      - email: bob
+        id: aaaaaaaa-0000-0000-0000-000000000002
+        limitIp: 5
+        totalGB: 1073741824
+      - email: dave
+  - remark: new
+    port: 10003
+    protocol: vless
+    settings:
+      decryption: none
+    clients:
+      - email: erin
+"#;
+
+fn current() -> Vec<Inbound> {
+    parse_inbounds(json!({ "success": true, "msg": "", "obj": inbounds_json() })).unwrap()
+}
+
+#[test]
+fn test_plan_without_prune() {
+    let desired = PanelState::from_yaml(DESIRED).unwrap();
+    let plan = Reconciler::new(desired).plan_against(&current()).unwrap();
+
+    let kinds: Vec<&str> = plan
+        .changes
+        .iter()
+        .map(|change| match change {
+            Change::CreateInbound { .. } => "create-inbound",
+            Change::UpdateInbound { .. } => "update-inbound",
+            Change::DeleteInbound { .. } => "delete-inbound",
+            Change::AddClient { .. } => "add-client",
+            Change::UpdateClient { .. } => "update-client",
+            Change::DeleteClient { .. } => "delete-client",
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            "update-inbound",
+            "create-inbound",
+            "update-client",
+            "add-client"
+        ]
+    );
+
+    let Change::UpdateInbound {
+        changes, inbound, ..
+    } = &plan.changes[0]
+    else {
+        unreachable!()
+    };
+    let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+    assert_eq!(fields, ["remark", "streamSettings.network"]);
+    // Keys not declared in the desired state are kept
+    assert_eq!(inbound.stream_settings["security"], "none");
+    assert_eq!(inbound.clients().unwrap().len(), 2);
+
+    let Change::CreateInbound { inbound } = &plan.changes[1] else {
+        unreachable!()
+    };
+    let clients = inbound.clients().unwrap();
+    assert_eq!(clients[0].email, "erin");
+    assert!(clients[0].id.is_some());
+    assert_eq!(inbound.settings["decryption"], "none");
+
+    // bob keeps his subId, only the limit changes
+    let Change::UpdateClient {
+        key,
+        changes,
+        client,
+        ..
+    } = &plan.changes[2]
+    else {
+        unreachable!()
+    };
+    assert_eq!(key, "aaaaaaaa-0000-0000-0000-000000000002");
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].to_string(), "limitIp: 2 -> 5");
+    assert_eq!(client.sub_id, "sub-2");
+
+    let text = plan.to_string();
+    assert!(text.contains("~ inbound #1 :10001 \"vmess-renamed\""));
+    assert!(text.contains("    remark: \"vmess\" -> \"vmess-renamed\""));
+    assert!(text.contains("+ inbound :10003 \"new\" (vless)\n    + client erin"));
+    assert!(text.contains("+ client dave in inbound #1"));
+}
+
+#[test]
+fn test_plan_with_prune_and_no_changes() {
+    let state = json!({
+        "inbounds": [{
+            "remark": "vmess",
+            "port": 10001,
+            "protocol": "vmess",
+            "clients": [
+                {"email": "alice", "id": "aaaaaaaa-0000-0000-0000-000000000001", "comment": "vip"}
+            ]
+        }]
+    });
+    let desired = PanelState::from_json(&state.to_string()).unwrap();
+    let plan = Reconciler::new(desired.clone())
+        .prune(true)
+        .plan_against(&current())
+        .unwrap();
+
+    let lines: Vec<String> = plan.changes.iter().map(|c| c.to_string()).collect();
+    assert_eq!(
+        lines,
+        [
+            "- client bob in inbound #1",
+            "- inbound #2 :10002 \"trojan\""
+        ]
+    );
+
+    let plan = Reconciler::new(desired).plan_against(&current()).unwrap();
+    assert!(plan.is_empty());
+    assert_eq!(plan.to_string(), "No changes.");
+}
+
+#[tokio::test]
+async fn test_plan_and_apply() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    let list_mock = mock_inbounds(&server, inbounds_json());
+    let delete_client_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/1/delClient/aaaaaaaa-0000-0000-0000-000000000001/");
+        then.status(200).json_body(ok_response());
+    });
+    let update_client_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/updateClient/carol-secret/")
+            .body_contains(r#"\"enable\":false"#);
+        then.status(200).json_body(ok_response());
+    });
+
+    let state = json!({
+        "inbounds": [
+            {"remark": "vmess", "port": 10001, "protocol": "vmess", "clients": [
+                {"email": "bob", "id": "aaaaaaaa-0000-0000-0000-000000000002", "limitIp": 2, "totalGB": 1073741824_u64}
+            ]},
+            {"remark": "trojan", "port": 10002, "protocol": "trojan", "clients": [
+                {"email": "carol", "password": "carol-secret", "enable": false}
+            ]}
+        ]
+    });
+    let reconciler =
+        Reconciler::new(PanelState::from_json(&state.to_string()).unwrap()).prune(true);
+    let plan = reconciler.plan(&mut client).await.unwrap();
+    assert_eq!(plan.changes.len(), 2);
+
+    plan.apply(&mut client).await.unwrap();
+
+    list_mock.assert();
+    delete_client_mock.assert();
+    update_client_mock.assert();
+}
+
+#[tokio::test]
+async fn test_apply_prune_with_inbound_update() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    mock_inbounds(&server, inbounds_json());
+    let delete_client_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/1/delClient/aaaaaaaa-0000-0000-0000-000000000002/");
+        then.status(200).json_body(ok_response());
+    });
+    let update_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/update/1/")
+            .body_contains("vmess-renamed")
+            .body_contains(r#"\"email\":\"alice\""#)
+            .matches(|req| {
+                !String::from_utf8_lossy(req.body.as_deref().unwrap_or_default()).contains("bob")
+            });
+        then.status(200).json_body(ok_response());
+    });
+
+    let state = json!({
+        "inbounds": [{"remark": "vmess-renamed", "port": 10001, "protocol": "vmess", "clients": [
+            {"email": "alice", "id": "aaaaaaaa-0000-0000-0000-000000000001"}
+        ]}]
+    });
+    let desired = PanelState::from_json(&state.to_string()).unwrap();
+    let plan = Reconciler::new(desired)
+        .prune(true)
+        .plan(&mut client)
+        .await
+        .unwrap();
+    let lines: Vec<String> = plan.changes.iter().map(|c| c.to_string()).collect();
+    assert_eq!(
+        lines,
+        [
+            "- client bob in inbound #1",
+            "- inbound #2 :10002 \"trojan\"",
+            "~ inbound #1 :10001 \"vmess-renamed\"\n    remark: \"vmess\" -> \"vmess-renamed\""
+        ]
+    );
+
+    server.mock(|when, then| {
+        when.method(POST).path("/panel/api/inbounds/del/2/");
+        then.status(200).json_body(ok_response());
+    });
+    plan.apply(&mut client).await.unwrap();
+
+    delete_client_mock.assert();
+    update_mock.assert();
+}
+
+#[test]
+fn test_undeclared_client_fields_are_not_managed() {
+    let mut inbounds = inbounds_json();
+    let mut settings: serde_json::Value =
+        serde_json::from_str(inbounds[0]["settings"].as_str().unwrap()).unwrap();
+    settings["clients"][1]["expiryTime"] = json!(1_900_000_000_000_i64);
+    settings["clients"][1]["enable"] = json!(false);
+    inbounds[0]["settings"] = json!(settings.to_string());
+    let current = parse_inbounds(json!({ "success": true, "msg": "", "obj": inbounds })).unwrap();
+
+    let desired = PanelState::from_yaml(
+        r#"
+inbounds:
+  - remark: vmess
+    port: 10001
+    protocol: vmess
+    clients:
+      - email: alice
+      - email: bob
+"#,
+    )
+    .unwrap();
+    let plan = Reconciler::new(desired).plan_against(&current).unwrap();
+    assert!(plan.is_empty(), "{}", plan);
+
+    let desired = PanelState::from_yaml(
+        r#"
+inbounds:
+  - remark: vmess
+    port: 10001
+    protocol: vmess
+    clients:
+      - email: bob
+        enable: true
+"#,
+    )
+    .unwrap();
+    let plan = Reconciler::new(desired).plan_against(&current).unwrap();
+    let Change::UpdateClient {
+        changes, client, ..
+    } = &plan.changes[0]
+    else {
+        unreachable!()
+    };
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].to_string(), "enable: false -> true");
+    assert_eq!(client.total_gb, 1073741824);
+    assert_eq!(client.expiry_time, 1_900_000_000_000);
+}
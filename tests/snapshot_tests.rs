@@ -0,0 +1,105 @@
+mod common;
+
+use common::*;
+use httpmock::prelude::*;
+use serde_json::json;
+use xui_rs::snapshot::{ImportOptions, ImportOutcome, SNAPSHOT_VERSION, Snapshot};
+
+#[tokio::test]
+async fn test_export_snapshot_round_trip() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+    mock_inbounds(&server, inbounds_json());
+
+    let snapshot = client.export_snapshot().await.unwrap();
+    assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+    assert_eq!(snapshot.source, server.url("/"));
+    assert_eq!(snapshot.inbounds.len(), 2);
+    assert_eq!(snapshot.inbounds[0].client_stats[1].down, 40);
+
+    let parsed = Snapshot::from_json(&snapshot.to_json().unwrap()).unwrap();
+    assert_eq!(parsed, snapshot);
+
+    let mut future = serde_json::to_value(&snapshot).unwrap();
+    future["version"] = json!(SNAPSHOT_VERSION + 1);
+    assert!(Snapshot::from_json(&future.to_string()).is_err());
+}
+
+#[tokio::test]
+async fn test_import_snapshot() {
+    let source = setup_mock_server();
+    let mut source_client = logged_in_client(&source).await;
+    mock_inbounds(&source, inbounds_json());
+    let snapshot = source_client.export_snapshot().await.unwrap();
+
+    let target = setup_mock_server();
+    let mut client = logged_in_client(&target).await;
+
+    // The VMess inbound moves to port 20001 with reset traffic
+    let vmess_mock = target.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/add/")
+            .json_body_partial(
+                r#"{"port": 20001, "tag": "inbound-10.0.0.1:20001", "listen": "10.0.0.1", "up": 0}"#,
+            );
+        then.status(200).json_body(ok_response());
+    });
+    // The Trojan port is already taken on the target panel
+    let trojan_mock = target.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/add/")
+            .json_body_partial(r#"{"port": 10002}"#);
+        then.status(200)
+            .json_body(json!({"success": false, "msg": "Port already exists", "obj": null}));
+    });
+
+    let options = ImportOptions::new()
+        .remap_port(10001, 20001)
+        .remap_listen("", "10.0.0.1");
+    let report = client.import_snapshot(&snapshot, &options).await.unwrap();
+
+    assert_eq!(report.items.len(), 2);
+    assert_eq!(report.items[0].port, 20001);
+    assert_eq!(report.items[0].outcome, ImportOutcome::Created);
+    assert!(!report.is_success());
+    assert_eq!(report.failed()[0].remark, "trojan");
+
+    vmess_mock.assert();
+    trojan_mock.assert();
+}
+
+#[tokio::test]
+async fn test_import_snapshot_keep_traffic() {
+    let source = setup_mock_server();
+    let mut source_client = logged_in_client(&source).await;
+    mock_inbounds(&source, inbounds_json());
+    let mut snapshot = source_client.export_snapshot().await.unwrap();
+    // Only the Trojan inbound, with inbound counters set
+    snapshot.inbounds.remove(0);
+    snapshot.inbounds[0].up = 100;
+    snapshot.inbounds[0].down = 200;
+
+    for (keep_traffic, [up, down, client_up, client_down]) in
+        [(true, [100, 200, 50, 60]), (false, [0, 0, 0, 0])]
+    {
+        let target = setup_mock_server();
+        let mut client = logged_in_client(&target).await;
+        let expected = json!({
+            "up": up,
+            "down": down,
+            "clientStats": [{"email": "carol", "up": client_up, "down": client_down}]
+        });
+        let add_mock = target.mock(|when, then| {
+            when.method(POST)
+                .path("/panel/api/inbounds/add/")
+                .json_body_partial(expected.to_string());
+            then.status(200).json_body(ok_response());
+        });
+
+        let options = ImportOptions::new().keep_traffic(keep_traffic);
+        let report = client.import_snapshot(&snapshot, &options).await.unwrap();
+
+        assert!(report.is_success());
+        add_mock.assert();
+    }
+}
@@ -0,0 +1,86 @@
+use xui_rs::poller::{ClientDelta, TrafficSample};
+use xui_rs::store::{JsonlStore, MemoryStore, TrafficStore};
+
+const DAY: i64 = 24 * 60 * 60 * 1000;
+
+fn sample(to: i64, deltas: &[(&str, u64, u64)]) -> TrafficSample {
+    TrafficSample {
+        from: to - 60_000,
+        to,
+        clients: deltas
+            .iter()
+            .map(|(email, up, down)| ClientDelta {
+                inbound_id: 1,
+                email: email.to_string(),
+                up: *up,
+                down: *down,
+                reset: false,
+            })
+            .collect(),
+        inbounds: Vec::new(),
+    }
+}
+
+fn record_history(store: &mut impl TrafficStore) {
+    store
+        .record(&sample(DAY + 1_000, &[("alice", 10, 100), ("bob", 0, 0)]))
+        .unwrap();
+    store
+        .record(&sample(DAY + 2_000, &[("alice", 5, 5), ("bob", 1, 1)]))
+        .unwrap();
+    store
+        .record(&sample(2 * DAY + 1_000, &[("bob", 500, 500)]))
+        .unwrap();
+}
+
+fn check_queries(store: &impl TrafficStore) {
+    // Idle clients are not recorded
+    assert_eq!(store.samples(0, i64::MAX).unwrap()[0].clients.len(), 1);
+
+    let alice = store.client_usage("alice", DAY, 2 * DAY).unwrap();
+    assert_eq!((alice.up, alice.down), (15, 105));
+    assert_eq!(store.client_usage("carol", 0, i64::MAX).unwrap().total(), 0);
+
+    let daily = store.daily_usage(0, i64::MAX).unwrap();
+    let days: Vec<(i64, &str, u64)> = daily
+        .iter()
+        .map(|usage| (usage.day, usage.email.as_str(), usage.total()))
+        .collect();
+    assert_eq!(
+        days,
+        [(DAY, "alice", 120), (DAY, "bob", 2), (2 * DAY, "bob", 1000)]
+    );
+
+    let top = store.top_clients(1, 0, i64::MAX).unwrap();
+    assert_eq!(top.len(), 1);
+    assert_eq!((top[0].email.as_str(), top[0].total()), ("bob", 1002));
+    // The end of a range is excluded
+    assert_eq!(
+        store.top_clients(5, 0, 2 * DAY + 1_000).unwrap()[0].email,
+        "alice"
+    );
+}
+
+#[test]
+fn test_memory_store() {
+    let mut store = MemoryStore::new();
+    record_history(&mut store);
+    check_queries(&store);
+}
+
+#[test]
+fn test_jsonl_store() {
+    let path = std::env::temp_dir().join(format!("xui-rs-store-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut store = JsonlStore::new(&path);
+    assert!(store.samples(0, i64::MAX).unwrap().is_empty());
+
+    record_history(&mut store);
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 3);
+    // A reopened store sees the same history
+    check_queries(&JsonlStore::new(&path));
+
+    std::fs::write(&path, "not json\n").unwrap();
+    assert!(store.samples(0, i64::MAX).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,291 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use httpmock::prelude::*;
+use serde_json::json;
+use std::time::Duration;
+use xui_rs::api::XUiClient;
+use xui_rs::links::{ShareLink, StreamParams};
+use xui_rs::models::{Inbound, Protocol, parse_inbounds};
+use xui_rs::subscription::{SubscriptionBuilder, SubscriptionClient, SubscriptionUserInfo};
+
+// Helper building a list response with a VLESS Reality and a Trojan WS inbound
+fn inbounds_response() -> serde_json::Value {
+    json!({
+        "success": true,
+        "msg": "",
+        "obj": [
+            {
+                "id": 1,
+                "up": 0,
+                "down": 0,
+                "total": 0,
+                "remark": "reality",
+                "enable": true,
+                "expiryTime": 0,
+                "clientStats": [
+                    {"id": 1, "inboundId": 1, "enable": true, "email": "alice", "up": 100, "down": 200, "expiryTime": 1767225600000_i64, "total": 1000, "reset": 0}
+                ],
+                "listen": "",
+                "port": 443,
+                "protocol": "vless",
+                "settings": "{\"clients\":[{\"id\":\"11111111-2222-3333-4444-555555555555\",\"flow\":\"xtls-rprx-vision\",\"email\":\"alice\",\"limitIp\":0,\"totalGB\":1000,\"expiryTime\":1767225600000,\"enable\":true,\"tgId\":\"\",\"subId\":\"sub-a\",\"reset\":0},{\"id\":\"66666666-2222-3333-4444-555555555555\",\"email\":\"bob\",\"enable\":true,\"subId\":\"sub-b\"}],\"decryption\":\"none\"}",
+                "streamSettings": "{\"network\":\"tcp\",\"security\":\"reality\",\"realitySettings\":{\"serverNames\":[\"www.example.com\"],\"shortIds\":[\"ab12\"],\"settings\":{\"publicKey\":\"PUBKEY\",\"fingerprint\":\"chrome\",\"spiderX\":\"/\"}},\"tcpSettings\":{\"header\":{\"type\":\"none\"}}}",
+                "tag": "inbound-443",
+                "sniffing": "{\"enabled\":true,\"destOverride\":[\"http\",\"tls\"]}"
+            },
+            {
+                "id": 2,
+                "remark": "trojan",
+                "enable": true,
+                "clientStats": null,
+                "port": 8443,
+                "protocol": "trojan",
+                "settings": "{\"clients\":[{\"password\":\"secret\",\"email\":\"alice-tr\",\"enable\":true,\"subId\":\"sub-a\",\"totalGB\":0,\"expiryTime\":0}]}",
+                "streamSettings": "{\"network\":\"ws\",\"security\":\"tls\",\"wsSettings\":{\"path\":\"/ws\",\"headers\":{\"Host\":\"cdn.example.com\"}},\"tlsSettings\":{\"serverName\":\"cdn.example.com\",\"alpn\":[\"h2\",\"http/1.1\"]}}",
+                "tag": "inbound-8443",
+                "sniffing": ""
+            }
+        ]
+    })
+}
+
+fn inbounds() -> Vec<Inbound> {
+    parse_inbounds(inbounds_response()).unwrap()
+}
+
+#[test]
+fn test_parse_inbounds() {
+    let inbounds = inbounds();
+    assert_eq!(inbounds.len(), 2);
+
+    let clients = inbounds[0].clients().unwrap();
+    assert_eq!(clients.len(), 2);
+    assert_eq!(clients[0].flow, "xtls-rprx-vision");
+    assert_eq!(clients[0].extra["tgId"], json!(""));
+    assert_eq!(inbounds[0].security(), "reality");
+    assert!(inbounds[1].client_stats.is_empty());
+    assert!(inbounds[1].sniffing.is_null());
+
+    // Settings are encoded back into JSON strings
+    let encoded = serde_json::to_value(&inbounds[0]).unwrap();
+    assert!(encoded["settings"].is_string());
+    assert!(encoded["streamSettings"].is_string());
+}
+
+#[test]
+fn test_share_link_vless_reality() {
+    let inbounds = inbounds();
+    let client = &inbounds[0].clients().unwrap()[0];
+    let link = ShareLink::from_inbound(&inbounds[0], client, "vpn.example.com")
+        .unwrap()
+        .to_uri()
+        .unwrap();
+
+    assert!(link.starts_with("vless://11111111-2222-3333-4444-555555555555@vpn.example.com:443?"));
+    assert!(link.contains("security=reality"));
+    assert!(link.contains("pbk=PUBKEY"));
+    assert!(link.contains("sid=ab12"));
+    assert!(link.contains("flow=xtls-rprx-vision"));
+    assert!(link.ends_with("#reality-alice"));
+}
+
+#[test]
+fn test_subscription_outputs() {
+    let mut builder = SubscriptionBuilder::new("sub-a");
+    builder
+        .add_inbounds("vpn.example.com", &inbounds())
+        .unwrap();
+    assert_eq!(builder.entries().len(), 2);
+
+    let decoded =
+        String::from_utf8(STANDARD.decode(builder.to_base64().unwrap()).unwrap()).unwrap();
+    let lines: Vec<&str> = decoded.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("vless://"));
+    assert!(lines[1].starts_with("trojan://secret@vpn.example.com:8443?"));
+
+    let clash = builder.to_clash_yaml().unwrap();
+    assert!(clash.contains("reality-opts"));
+    assert!(clash.contains("MATCH,PROXY"));
+
+    let sing_box = builder.to_sing_box_json().unwrap();
+    let outbounds = sing_box["outbounds"].as_array().unwrap();
+    assert_eq!(outbounds.len(), 4);
+    assert_eq!(outbounds[2]["transport"]["type"], "ws");
+
+    let xray = builder.to_xray_json().unwrap();
+    assert_eq!(xray.as_array().unwrap().len(), 2);
+    assert_eq!(
+        xray[0]["outbounds"][0]["streamSettings"]["realitySettings"]["publicKey"],
+        "PUBKEY"
+    );
+}
+
+#[test]
+fn test_subscription_user_info() {
+    let mut builder = SubscriptionBuilder::new("sub-a");
+    builder
+        .add_inbounds("vpn.example.com", &inbounds())
+        .unwrap();
+
+    // The Trojan client is unlimited, so the aggregated quota and expiry are unlimited
+    let info = builder.user_info();
+    assert_eq!(
+        info,
+        SubscriptionUserInfo {
+            upload: 100,
+            download: 200,
+            total: 0,
+            expire: 0
+        }
+    );
+
+    let mut builder = SubscriptionBuilder::new("sub-a");
+    builder
+        .add_inbounds("vpn.example.com", &inbounds()[..1])
+        .unwrap();
+    assert_eq!(
+        builder.user_info().to_string(),
+        "upload=100; download=200; total=1000; expire=1767225600"
+    );
+}
+
+#[tokio::test]
+async fn test_subscription_from_panel() {
+    let server = MockServer::start();
+
+    let login_mock = server.mock(|when, then| {
+        when.method(POST).path("/login/");
+        then.status(200)
+            .header("set-cookie", "session=test-cookie; Max-Age=3600; Path=/");
+    });
+
+    let inbounds_mock = server.mock(|when, then| {
+        when.method(GET).path("/panel/api/inbounds/list/");
+        then.status(200).json_body(inbounds_response());
+    });
+
+    let mut client = XUiClient::new(server.url("/")).unwrap();
+    client.login("user", "pass").await.unwrap();
+
+    let mut builder = SubscriptionBuilder::new("sub-b");
+    builder
+        .add_panel(&mut client, "vpn.example.com")
+        .await
+        .unwrap();
+    assert_eq!(builder.entries().len(), 1);
+    assert_eq!(builder.entries()[0].client.email, "bob");
+
+    login_mock.assert();
+    inbounds_mock.assert();
+}
+
+#[test]
+fn test_share_link_round_trip() {
+    let inbounds = inbounds();
+    for inbound in &inbounds {
+        for client in inbound.clients().unwrap() {
+            let link = ShareLink::from_inbound(inbound, &client, "vpn.example.com").unwrap();
+            let parsed: ShareLink = link.to_uri().unwrap().parse().unwrap();
+            assert_eq!(parsed, link);
+        }
+    }
+
+    let vmess = ShareLink {
+        protocol: Protocol::Vmess,
+        address: "vpn.example.com".to_string(),
+        port: 8080,
+        credential: "11111111-2222-3333-4444-555555555555".to_string(),
+        remark: "vmess grpc".to_string(),
+        stream: StreamParams {
+            network: "grpc".to_string(),
+            security: "tls".to_string(),
+            service_name: "svc".to_string(),
+            mode: "gun".to_string(),
+            sni: "vpn.example.com".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let parsed: ShareLink = vmess.to_uri().unwrap().parse().unwrap();
+    assert_eq!(parsed, vmess);
+
+    let ss: ShareLink = "ss://YWVzLTI1Ni1nY206cGFzcw@1.2.3.4:8388#my%20ss"
+        .parse()
+        .unwrap();
+    assert_eq!(ss.method, "aes-256-gcm");
+    assert_eq!(ss.credential, "pass");
+    assert_eq!(ss.port, 8388);
+    assert_eq!(ss.remark, "my ss");
+
+    assert!("hysteria2://x@host:443".parse::<ShareLink>().is_err());
+}
+
+#[test]
+fn test_parse_user_info_header() {
+    let info: SubscriptionUserInfo = "upload=1; download=2.0; total=3; expire=4".parse().unwrap();
+    assert_eq!(
+        info,
+        SubscriptionUserInfo {
+            upload: 1,
+            download: 2,
+            total: 3,
+            expire: 4
+        }
+    );
+    assert!("upload".parse::<SubscriptionUserInfo>().is_err());
+}
+
+#[tokio::test]
+async fn test_subscription_client_fetch() {
+    let server = MockServer::start();
+
+    let mut builder = SubscriptionBuilder::new("sub-a");
+    builder
+        .add_inbounds("vpn.example.com", &inbounds())
+        .unwrap();
+    let body = builder.to_base64().unwrap();
+
+    let sub_mock = server.mock(|when, then| {
+        when.method(GET).path("/sub/sub-a");
+        then.status(200)
+            .header(
+                "subscription-userinfo",
+                "upload=100; download=200; total=0; expire=0",
+            )
+            .header("profile-update-interval", "12")
+            .header("profile-title", "base64:bXkgdnBu")
+            .body(body);
+    });
+
+    let json_mock = server.mock(|when, then| {
+        when.method(GET).path("/json/sub-a");
+        then.status(200).json_body(builder.to_xray_json().unwrap());
+    });
+
+    let missing_mock = server.mock(|when, then| {
+        when.method(GET).path("/sub/unknown");
+        then.status(400);
+    });
+
+    let client = SubscriptionClient::new().unwrap();
+    let subscription = client.fetch(server.url("/sub/sub-a")).await.unwrap();
+    assert_eq!(subscription.links, builder.links().unwrap());
+    assert_eq!(subscription.uris.len(), 2);
+    assert_eq!(subscription.headers.user_info, Some(builder.user_info()));
+    assert_eq!(
+        subscription.headers.update_interval,
+        Some(Duration::from_secs(12 * 3600))
+    );
+    assert_eq!(subscription.headers.title.as_deref(), Some("my vpn"));
+
+    let json = client.fetch_json(server.url("/json/sub-a")).await.unwrap();
+    assert_eq!(json.configs.len(), 2);
+    assert!(json.headers.user_info.is_none());
+
+    assert!(client.fetch(server.url("/sub/unknown")).await.is_err());
+
+    sub_mock.assert();
+    json_mock.assert();
+    missing_mock.assert();
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::*;
+use serde_json::json;
+use xui_rs::models::{Inbound, parse_inbounds};
+use xui_rs::validate::{Severity, validate_inbound};
+
+fn existing() -> Vec<Inbound> {
+    parse_inbounds(json!({ "success": true, "msg": "", "obj": inbounds_json() })).unwrap()
+}
+
+fn inbound(value: serde_json::Value) -> Inbound {
+    serde_json::from_value(value).unwrap()
+}
+
+fn fields(inbound: &Inbound) -> Vec<(Severity, String)> {
+    validate_inbound(inbound, &existing())
+        .diagnostics
+        .into_iter()
+        .map(|d| (d.severity, d.field))
+        .collect()
+}
+
+#[test]
+fn test_valid_reality_inbound() {
+    let inbound = inbound(json!({
+        "port": 443,
+        "protocol": "vless",
+        "settings": {"clients": [
+            {"id": "11111111-2222-3333-4444-555555555555", "email": "new", "flow": "xtls-rprx-vision"}
+        ], "decryption": "none"},
+        "streamSettings": {"network": "tcp", "security": "reality", "realitySettings": {
+            "dest": "www.example.com:443",
+            "serverNames": ["www.example.com"],
+            "privateKey": "PRIVATE",
+            "shortIds": ["ab12"]
+        }}
+    }));
+
+    let report = validate_inbound(&inbound, &existing());
+    assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+    assert!(report.is_valid());
+}
+
+#[test]
+fn test_invalid_reality_inbound() {
+    let inbound = inbound(json!({
+        "port": 10001,
+        "protocol": "vmess",
+        "settings": {"clients": [
+            {"id": "not-a-uuid", "email": "alice", "flow": "xtls-rprx-vision"},
+            {"id": "11111111-2222-3333-4444-555555555555", "email": "dup"},
+            {"id": "11111111-2222-3333-4444-555555555556", "email": "dup"}
+        ]},
+        "streamSettings": {"network": "ws", "security": "reality", "realitySettings": {
+            "serverNames": []
+        }}
+    }));
+
+    let report = validate_inbound(&inbound, &existing());
+    assert!(!report.is_valid());
+    assert_eq!(
+        fields(&inbound),
+        [
+            (Severity::Error, "port".to_string()),
+            (Severity::Error, "streamSettings.security".to_string()),
+            (Severity::Error, "streamSettings.security".to_string()),
+            (
+                Severity::Error,
+                "streamSettings.realitySettings.dest".to_string()
+            ),
+            (
+                Severity::Error,
+                "streamSettings.realitySettings.serverNames".to_string()
+            ),
+            (
+                Severity::Error,
+                "streamSettings.realitySettings.privateKey".to_string()
+            ),
+            (
+                Severity::Warning,
+                "streamSettings.realitySettings.shortIds".to_string()
+            ),
+            (Severity::Error, "settings.clients[0].email".to_string()),
+            (Severity::Error, "settings.clients[0].id".to_string()),
+            (Severity::Error, "settings.clients[0].flow".to_string()),
+            (Severity::Error, "settings.clients[2].email".to_string()),
+        ]
+    );
+    assert_eq!(
+        report.errors()[0].to_string(),
+        "error: port: port 10001 is already used by inbound 1 \"vmess\""
+    );
+}
+
+#[test]
+fn test_tls_and_updates() {
+    let trojan = inbound(json!({
+        "port": 8443,
+        "listen": "10.0.0.1",
+        "protocol": "trojan",
+        "settings": {"clients": [{"password": "", "email": "t1"}]},
+        "streamSettings": {"network": "grpc", "security": "tls", "tlsSettings": {
+            "certificates": [{"certificateFile": "/etc/cert.pem"}]
+        }}
+    }));
+    assert_eq!(
+        fields(&trojan),
+        [
+            (
+                Severity::Error,
+                "streamSettings.tlsSettings.certificates[0]".to_string()
+            ),
+            (
+                Severity::Warning,
+                "streamSettings.tlsSettings.serverName".to_string()
+            ),
+            (Severity::Error, "settings.clients[0].password".to_string()),
+        ]
+    );
+
+    // An existing inbound does not conflict with itself
+    let current = existing();
+    assert!(validate_inbound(&current[1], &current).is_valid());
+}
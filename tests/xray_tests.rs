@@ -0,0 +1,189 @@
+mod common;
+
+use common::*;
+use httpmock::prelude::*;
+use serde_json::json;
+use xui_rs::builder::InboundBuilder;
+use xui_rs::keys;
+use xui_rs::models::{Inbound, Protocol, parse_inbounds};
+use xui_rs::snapshot::ImportOutcome;
+use xui_rs::xray::{XrayConfigBuilder, XrayImport};
+
+fn inbounds() -> Vec<Inbound> {
+    parse_inbounds(json!({ "success": true, "msg": "", "obj": inbounds_json() })).unwrap()
+}
+
+#[test]
+fn test_xray_config_from_inbounds() {
+    let mut inbounds = inbounds();
+    // Disabled clients are left out of the generated configuration
+    inbounds[0].client_stats[1].enable = false;
+
+    inbounds.push(InboundBuilder::vless_reality(443).build().unwrap());
+
+    let config = XrayConfigBuilder::new().build(&inbounds).unwrap();
+
+    let xray_inbounds = config["inbounds"].as_array().unwrap();
+    assert_eq!(xray_inbounds.len(), 4);
+    assert_eq!(xray_inbounds[0]["tag"], "api");
+    assert_eq!(xray_inbounds[0]["port"], 62789);
+
+    let vmess = &xray_inbounds[1];
+    assert_eq!(vmess["tag"], "inbound-10001");
+    assert_eq!(vmess["listen"], json!(null));
+    assert_eq!(
+        vmess["settings"]["clients"],
+        json!([{"email": "alice", "id": "aaaaaaaa-0000-0000-0000-000000000001"}])
+    );
+
+    let trojan = &xray_inbounds[2];
+    assert_eq!(trojan["settings"]["clients"][0]["password"], "carol-secret");
+
+    let vless = &xray_inbounds[3];
+    assert_eq!(vless["settings"]["clients"][0]["flow"], "xtls-rprx-vision");
+    assert!(vless["streamSettings"]["realitySettings"]["privateKey"].is_string());
+    assert!(vless["streamSettings"]["realitySettings"]["settings"].is_null());
+
+    assert_eq!(config["stats"], json!({}));
+    assert_eq!(config["policy"]["levels"]["0"]["statsUserUplink"], true);
+    assert_eq!(config["routing"]["rules"][0]["outboundTag"], "api");
+    assert_eq!(config["outbounds"][0]["protocol"], "freedom");
+}
+
+#[test]
+fn test_xray_config_without_api() {
+    let mut inbounds = inbounds();
+    inbounds[1].enable = false;
+
+    let config = XrayConfigBuilder::new()
+        .api_port(None)
+        .log_level("error")
+        .outbounds(vec![json!({"tag": "out", "protocol": "freedom"})])
+        .build(&inbounds)
+        .unwrap();
+
+    assert_eq!(config["inbounds"].as_array().unwrap().len(), 1);
+    assert!(config["api"].is_null());
+    assert!(config["stats"].is_null());
+    assert_eq!(config["log"]["loglevel"], "error");
+    assert_eq!(
+        config["outbounds"],
+        json!([{"tag": "out", "protocol": "freedom"}])
+    );
+}
+
+#[test]
+fn test_xray_import() {
+    let reality_keys = keys::reality_keypair();
+    let config = json!({
+        "inbounds": [
+            {"tag": "api", "listen": "127.0.0.1", "port": 62789, "protocol": "dokodemo-door", "settings": {"address": "127.0.0.1"}},
+            {
+                "tag": "vless-in",
+                "port": 443,
+                "protocol": "vless",
+                "settings": {"clients": [{"id": "aaaaaaaa-0000-0000-0000-000000000003", "flow": "xtls-rprx-vision"}], "decryption": "none"},
+                "streamSettings": {
+                    "network": "tcp",
+                    "security": "reality",
+                    "realitySettings": {"dest": "www.example.com:443", "serverNames": ["www.example.com"], "privateKey": reality_keys.private_key, "shortIds": ["ab"]}
+                }
+            },
+            {"port": "8388", "protocol": "shadowsocks", "settings": {"method": "aes-256-gcm", "password": "ss-secret", "network": "tcp,udp"}},
+            {"tag": "socks-in", "listen": "10.0.0.1", "port": 1080, "protocol": "socks", "settings": {"auth": "noauth", "udp": true}},
+            {"tag": "range", "port": "2000-3000", "protocol": "vmess", "settings": {"clients": []}},
+            {"tag": "mixed-in", "port": 1081, "protocol": "mixed"}
+        ],
+        "outbounds": [{"protocol": "freedom"}]
+    });
+
+    let import = XrayImport::from_config(&config).unwrap();
+    assert_eq!(import.inbounds.len(), 3);
+    let skipped: Vec<&str> = import.skipped.iter().map(|s| s.tag.as_str()).collect();
+    assert_eq!(skipped, ["api", "range", "mixed-in"]);
+
+    let vless = &import.inbounds[0];
+    assert_eq!(vless.protocol, Protocol::Vless);
+    assert_eq!(vless.remark, "vless-in");
+    let clients = vless.clients().unwrap();
+    assert_eq!(clients[0].flow, "xtls-rprx-vision");
+    assert_eq!(clients[0].email.len(), 8);
+    assert_eq!(
+        vless.stream_settings["realitySettings"]["settings"]["publicKey"],
+        reality_keys.public_key
+    );
+
+    // A single-user Shadowsocks inbound becomes one client
+    let shadowsocks = &import.inbounds[1];
+    assert_eq!(shadowsocks.port, 8388);
+    assert_eq!(shadowsocks.tag, "inbound-8388");
+    let clients = shadowsocks.clients().unwrap();
+    assert_eq!(clients[0].password.as_deref(), Some("ss-secret"));
+    assert_eq!(clients[0].method.as_deref(), Some("aes-256-gcm"));
+
+    let socks = &import.inbounds[2];
+    assert_eq!(socks.listen, "10.0.0.1");
+    assert!(socks.stream_settings.is_null());
+    assert_eq!(socks.settings["auth"], "noauth");
+
+    // Generated configurations can be imported back
+    let exported = XrayConfigBuilder::new().build(&import.inbounds).unwrap();
+    let reimported = XrayImport::from_config(&exported).unwrap();
+    assert_eq!(reimported.inbounds.len(), 3);
+    assert_eq!(
+        reimported.inbounds[0].clients().unwrap()[0].id,
+        vless.clients().unwrap()[0].id
+    );
+}
+
+#[test]
+fn test_xray_import_skips_malformed_inbounds() {
+    let config = json!({
+        "inbounds": [
+            {"tag": "ss-in", "port": 8388, "protocol": "shadowsocks", "settings": {"password": "ss-secret"}},
+            {
+                "tag": "reality-in",
+                "port": 443,
+                "protocol": "vless",
+                "settings": {"clients": [{"id": "aaaaaaaa-0000-0000-0000-000000000004"}], "decryption": "none"},
+                "streamSettings": {
+                    "network": "tcp",
+                    "security": "reality",
+                    "realitySettings": {"dest": "www.example.com:443", "serverNames": ["www.example.com"], "shortIds": ["ab"]}
+                }
+            },
+            {"tag": "socks-in", "port": 1080, "protocol": "socks", "settings": {"auth": "noauth"}}
+        ]
+    });
+
+    let import = XrayImport::from_config(&config).unwrap();
+    assert_eq!(import.inbounds.len(), 1);
+    assert_eq!(import.inbounds[0].remark, "socks-in");
+    let skipped: Vec<&str> = import.skipped.iter().map(|s| s.tag.as_str()).collect();
+    assert_eq!(skipped, ["ss-in", "reality-in"]);
+    assert!(import.skipped[0].reason.contains("method"));
+    assert!(import.skipped[1].reason.contains("privateKey"));
+}
+
+#[tokio::test]
+async fn test_import_xray_config() {
+    let server = setup_mock_server();
+    let mut client = logged_in_client(&server).await;
+
+    let add_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/panel/api/inbounds/add/")
+            .json_body_partial(r#"{"port": 10086, "protocol": "vmess", "tag": "vmess-in"}"#);
+        then.status(200).json_body(ok_response());
+    });
+
+    let import = XrayImport::from_json(
+        r#"{"inbounds": [{"tag": "vmess-in", "port": 10086, "protocol": "vmess", "settings": {"clients": [{"id": "aaaaaaaa-0000-0000-0000-000000000004", "email": "erin"}]}}]}"#,
+    )
+    .unwrap();
+    let report = client.import_xray_config(&import).await.unwrap();
+
+    assert!(report.is_success());
+    assert_eq!(report.items[0].outcome, ImportOutcome::Created);
+    add_mock.assert();
+}